@@ -0,0 +1,200 @@
+use advent_of_code_2015::{
+    days,
+    harness::{self, alloc::TrackingAlloc, baseline::Baseline, bench, export, BenchConfig, Run},
+};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use std::{fs, path::PathBuf, time::Duration};
+
+#[global_allocator]
+static ALLOC: TrackingAlloc = TrackingAlloc::new();
+
+const USAGE: &str = "Usage:
+    harness run <day>[.<part>] [<part>|<other>] [--input <file>|-] [--bench] [--inputs <dir>]
+    harness verify [--answers <file>] [--inputs <dir>] [--allow-missing]
+    harness bench [--format table|json|csv] [--output <file>] [--budget <ms>] [--inputs <dir>]
+                  [--jobs <n>] [--save-baseline <file>] [--baseline <file>]";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let mut args = std::env::args().skip(1);
+    let command = args.next().ok_or_else(|| eyre!(USAGE))?;
+
+    let mut answers = PathBuf::from("answers.txt");
+    let mut inputs = PathBuf::from("inputs");
+    let mut format = Format::Table;
+    let mut output = None;
+    let mut budget = Duration::from_secs(1);
+    let mut save_baseline = None;
+    let mut baseline = None;
+    let mut input = None;
+    let mut run_bench = false;
+    let mut allow_missing = false;
+    let mut jobs = 1;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--input" => input = Some(PathBuf::from(value()?)),
+            "--bench" => run_bench = true,
+            "--allow-missing" => allow_missing = true,
+            "--answers" => answers = value()?.into(),
+            "--inputs" => inputs = value()?.into(),
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--save-baseline" => save_baseline = Some(PathBuf::from(value()?)),
+            "--baseline" => baseline = Some(PathBuf::from(value()?)),
+            "--format" => {
+                format = match value()?.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    f => return Err(eyre!("Unknown format `{}`\n{}", f, USAGE)),
+                }
+            }
+            "--jobs" => {
+                let n = value()?;
+                jobs = n
+                    .parse()
+                    .with_context(|| eyre!("Invalid job count `{}`", n))?;
+                if jobs == 0 {
+                    jobs = std::thread::available_parallelism().map_or(1, |n| n.get());
+                }
+            }
+            "--budget" => {
+                let ms = value()?;
+                let ms = ms
+                    .parse()
+                    .with_context(|| eyre!("Invalid budget `{}`", ms))?;
+                budget = Duration::from_millis(ms);
+            }
+            _ if !arg.starts_with("--") => positional.push(arg),
+            _ => return Err(eyre!("Unknown argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    if command != "run" && !positional.is_empty() {
+        return Err(eyre!("Unexpected argument `{}`\n{}", positional[0], USAGE));
+    }
+
+    match command.as_str() {
+        "run" => {
+            // Accept both `7 1` and the table's `7.1`.
+            let (day, selector) = match positional.as_slice() {
+                [day] => match day.split_once('.') {
+                    Some((day, part)) => (day, Some(part)),
+                    None => (day.as_str(), None),
+                },
+                [day, selector] => (day.as_str(), Some(selector.as_str())),
+                _ => return Err(eyre!(USAGE)),
+            };
+            let day = day
+                .parse()
+                .with_context(|| eyre!("Invalid day `{}`", day))?;
+
+            let entries = harness::select(days::SOLUTIONS, day, selector)?;
+            let path = input.unwrap_or_else(|| harness::input_path(&inputs, day));
+            let input = harness::load_input(&path)?;
+
+            let mut failed = false;
+            let mut records = Vec::new();
+            for (entry, f) in entries {
+                let result = if run_bench {
+                    let config = BenchConfig {
+                        alloc: &ALLOC,
+                        budget,
+                    };
+                    bench::bench_entry(day, entry, f, &input, config).map(|r| records.push(r))
+                } else {
+                    f(&input, &mut Run::default())
+                        .map(|answer| println!("{:>9} | {}", entry.label(day), answer))
+                };
+
+                if let Err(e) = result {
+                    eprintln!("{:>9} | ERROR | {:#}", entry.label(day), e);
+                    failed = true;
+                }
+            }
+
+            if run_bench {
+                print!("{}", export::to_table(&records));
+            }
+
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        "verify" => {
+            let answers = harness::Answers::load(&answers)?;
+            if !harness::verify(days::SOLUTIONS, &answers, &inputs, allow_missing)? {
+                std::process::exit(1);
+            }
+        }
+        "bench" => {
+            // Load the baseline up front so a bad path doesn't waste a full run.
+            let baseline = baseline.as_deref().map(Baseline::load).transpose()?;
+
+            let config = BenchConfig {
+                alloc: &ALLOC,
+                budget,
+            };
+
+            let mut records = Vec::new();
+            let mut failed = false;
+            for record in bench::bench_all(days::SOLUTIONS, &inputs, config, jobs) {
+                match record {
+                    Ok(record) => records.push(record),
+                    Err(e) => {
+                        eprintln!("{:#}", e);
+                        failed = true;
+                    }
+                }
+            }
+
+            let rendered = match format {
+                Format::Table => export::to_table(&records),
+                Format::Json => export::to_json(&records),
+                Format::Csv => export::to_csv(&records),
+            };
+
+            match output {
+                Some(path) => fs::write(&path, rendered)
+                    .with_context(|| eyre!("Unable to write results to {}", path.display()))?,
+                None => print!("{}", rendered),
+            }
+
+            if let Some(path) = save_baseline {
+                fs::write(&path, export::to_json(&records))
+                    .with_context(|| eyre!("Unable to write baseline to {}", path.display()))?;
+            }
+
+            if let Some(baseline) = baseline {
+                let regressions = baseline.compare(&records);
+                for (record, regressions) in &regressions {
+                    for regression in regressions {
+                        eprintln!("{:>9} | REGRESSION | {}", record.label(), regression);
+                    }
+                }
+
+                failed |= !regressions.is_empty();
+            }
+
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        _ => return Err(eyre!("Unknown command `{}`\n{}", command, USAGE)),
+    }
+
+    Ok(())
+}
@@ -1,27 +1,109 @@
-use aoc_lib::Day;
-
-mod aoc_1501;
-mod aoc_1502;
-mod aoc_1503;
-mod aoc_1504;
-mod aoc_1505;
-mod aoc_1506;
-mod aoc_1507;
-mod aoc_1508;
-mod aoc_1509;
-mod aoc_1510;
-mod aoc_1511;
-mod aoc_1512;
-mod aoc_1513;
-mod aoc_1514;
-mod aoc_1515;
-mod aoc_1516;
-mod aoc_1517;
-mod aoc_1518;
-mod aoc_1519;
-mod aoc_1520;
-mod aoc_1521;
-mod aoc_1523;
+use aoc_lib::{Bench, BenchResult, Day, UserError};
+use color_eyre::eyre::Result;
+
+use crate::harness::{Run, Solution};
+
+use std::fmt::Display;
+
+/// Builds a day's `DAY` for `aoc_lib::run` and `SOLUTION` for the harness from the same
+/// entry functions, so both binaries measure the same code.
+///
+/// Each entry function parses the input and returns the measured section, which
+/// computes the answer:
+///
+/// ```ignore
+/// pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+///     let containers = parse(input)?;
+///     Ok(move || part1(&containers, 150))
+/// }
+/// ```
+///
+/// If a day's printed answers carry more than the puzzle's answer, `answer:` names a
+/// function picking out the part that `harness verify` checks against `answers.txt`.
+macro_rules! day {
+    (
+        day: $day:literal,
+        name: $name:literal,
+        part_1: $part_1:path,
+        $(part_2: $part_2:path,)?
+        $(answer: $answer:path,)?
+        other: [$(($other_name:literal, $other:path)),* $(,)?] $(,)?
+    ) => {
+        pub const DAY: aoc_lib::Day = aoc_lib::Day {
+            day: $day,
+            name: $name,
+            part_1: |input, b| $crate::days::bench(input, b, $part_1),
+            part_2: day!(@optional $(|input, b| $crate::days::bench(input, b, $part_2))?),
+            other: &[$(($other_name, |input, b| $crate::days::bench(input, b, $other))),*],
+        };
+
+        pub const SOLUTION: $crate::harness::Solution = $crate::harness::Solution {
+            day: $day,
+            part_1: |input, r| $crate::days::solve(input, r, $part_1),
+            part_2: day!(@optional $(|input, r| $crate::days::solve(input, r, $part_2))?),
+            answer: day!(@answer $($answer)?),
+            other: &[$(($other_name, |input, r| $crate::days::solve(input, r, $other))),*],
+        };
+    };
+    (@optional) => { None };
+    (@optional $f:expr) => { Some($f) };
+    (@answer) => { $crate::harness::whole_answer };
+    (@answer $f:path) => { $f };
+}
+
+/// Runs an entry function under `aoc_lib`'s benchmarking.
+pub(crate) fn bench<'a, F, T>(
+    input: &'a str,
+    b: Bench,
+    entry: impl FnOnce(&'a str) -> Result<F>,
+) -> BenchResult
+where
+    F: Fn() -> Result<T>,
+    T: Display,
+{
+    let f = entry(input).map_err(UserError)?;
+    b.bench(f)
+}
+
+/// Runs an entry function under the harness.
+pub(crate) fn solve<'a, F, T>(
+    input: &'a str,
+    r: &mut Run,
+    entry: impl FnOnce(&'a str) -> Result<F>,
+) -> Result<String>
+where
+    F: Fn() -> Result<T>,
+    T: Display,
+{
+    let f = entry(input)?;
+    r.run(f)
+}
+
+pub mod aoc_1501;
+pub mod aoc_1502;
+pub mod aoc_1503;
+pub mod aoc_1504;
+pub mod aoc_1505;
+pub mod aoc_1506;
+pub mod aoc_1507;
+pub mod aoc_1508;
+pub mod aoc_1509;
+pub mod aoc_1510;
+pub mod aoc_1511;
+pub mod aoc_1512;
+pub mod aoc_1513;
+pub mod aoc_1514;
+pub mod aoc_1515;
+pub mod aoc_1516;
+pub mod aoc_1517;
+pub mod aoc_1518;
+pub mod aoc_1519;
+pub mod aoc_1520;
+pub mod aoc_1521;
+pub mod aoc_1522;
+pub mod aoc_1523;
+pub mod aoc_1524;
+pub mod aoc_1525;
 
 pub static DAYS: &[Day] = &[
     aoc_1501::DAY,
@@ -45,5 +127,36 @@ pub static DAYS: &[Day] = &[
     aoc_1519::DAY,
     aoc_1520::DAY,
     aoc_1521::DAY,
+    aoc_1522::DAY,
     aoc_1523::DAY,
+    aoc_1524::DAY,
+    aoc_1525::DAY,
+];
+
+pub static SOLUTIONS: &[Solution] = &[
+    aoc_1501::SOLUTION,
+    aoc_1502::SOLUTION,
+    aoc_1503::SOLUTION,
+    aoc_1504::SOLUTION,
+    aoc_1505::SOLUTION,
+    aoc_1506::SOLUTION,
+    aoc_1507::SOLUTION,
+    aoc_1508::SOLUTION,
+    aoc_1509::SOLUTION,
+    aoc_1510::SOLUTION,
+    aoc_1511::SOLUTION,
+    aoc_1512::SOLUTION,
+    aoc_1513::SOLUTION,
+    aoc_1514::SOLUTION,
+    aoc_1515::SOLUTION,
+    aoc_1516::SOLUTION,
+    aoc_1517::SOLUTION,
+    aoc_1518::SOLUTION,
+    aoc_1519::SOLUTION,
+    aoc_1520::SOLUTION,
+    aoc_1521::SOLUTION,
+    aoc_1522::SOLUTION,
+    aoc_1523::SOLUTION,
+    aoc_1524::SOLUTION,
+    aoc_1525::SOLUTION,
 ];
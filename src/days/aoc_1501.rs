@@ -1,25 +1,24 @@
 #![allow(clippy::unnecessary_wraps)]
 
-use aoc_lib::{Bench, BenchResult, Day};
 use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 1,
     name: "Not Quite Lisp",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part1(input))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<i64> + '_> {
+    Ok(move || part1(input))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part2(input))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<i64> + '_> {
+    Ok(move || part2(input))
 }
 
-fn part1(input: &str) -> Result<i64> {
+pub fn part1(input: &str) -> Result<i64> {
     Ok(input
         .chars()
         .map(|c| match c {
@@ -30,7 +29,7 @@ fn part1(input: &str) -> Result<i64> {
         .sum())
 }
 
-fn part2(input: &str) -> Result<i64> {
+pub fn part2(input: &str) -> Result<i64> {
     input
         .chars()
         .zip(1..)
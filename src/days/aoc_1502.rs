@@ -1,30 +1,30 @@
-use aoc_lib::{Bench, BenchResult, Day};
 use color_eyre::eyre::{eyre, Report, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 2,
     name: "I Was Told There Would Be No Math",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part(input, Box::paper))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u32> + '_> {
+    Ok(move || part(input, Present::paper))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part(input, Box::ribbon))
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u32> + '_> {
+    Ok(move || part(input, Present::ribbon))
 }
 
 #[derive(Debug, Copy, Clone)]
-struct Box {
+pub struct Present {
     width: u32,
     height: u32,
     length: u32,
 }
 
-impl Box {
-    fn paper(self) -> u32 {
+impl Present {
+    pub fn paper(self) -> u32 {
         let side_a_area = self.width * self.height;
         let side_b_area = self.width * self.length;
         let side_c_area = self.height * self.length;
@@ -34,7 +34,7 @@ impl Box {
         2 * (side_a_area + side_b_area + side_c_area) + min_side
     }
 
-    fn ribbon(self) -> u32 {
+    pub fn ribbon(self) -> u32 {
         let side_a = self.width.min(self.height).min(self.length);
         let side_b = if side_a == self.width {
             self.height.min(self.length)
@@ -52,7 +52,7 @@ impl Box {
     }
 }
 
-impl std::str::FromStr for Box {
+impl std::str::FromStr for Present {
     type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -64,21 +64,21 @@ impl std::str::FromStr for Box {
         );
 
         if let (Some(width), Some(height), Some(length)) = parts {
-            Ok(Box {
+            Ok(Present {
                 width,
                 height,
                 length,
             })
         } else {
-            Err(eyre!("Invalid Box definition: {}", s))
+            Err(eyre!("Invalid present definition: {}", s))
         }
     }
 }
 
-fn part(input: &str, f: fn(Box) -> u32) -> Result<u32> {
+pub fn part(input: &str, f: fn(Present) -> u32) -> Result<u32> {
     let mut total = 0;
 
-    for b in input.lines().map(str::parse::<Box>) {
+    for b in input.lines().map(str::parse::<Present>) {
         total += f(b?);
     }
 
@@ -91,13 +91,16 @@ mod tests_1502 {
 
     #[test]
     fn part1_examples() {
-        assert_eq!(58, "2x3x4".parse::<Box>().map(Box::paper).unwrap());
-        assert_eq!(43, "1x1x10".parse::<Box>().map(Box::paper).unwrap());
+        assert_eq!(58, "2x3x4".parse::<Present>().map(Present::paper).unwrap());
+        assert_eq!(43, "1x1x10".parse::<Present>().map(Present::paper).unwrap());
     }
 
     #[test]
     fn part2_examples() {
-        assert_eq!(34, "2x3x4".parse::<Box>().map(Box::ribbon).unwrap());
-        assert_eq!(14, "1x1x10".parse::<Box>().map(Box::ribbon).unwrap());
+        assert_eq!(34, "2x3x4".parse::<Present>().map(Present::ribbon).unwrap());
+        assert_eq!(
+            14,
+            "1x1x10".parse::<Present>().map(Present::ribbon).unwrap()
+        );
     }
 }
@@ -1,4 +1,3 @@
-use aoc_lib::{BenchResult, Day, UserError};
 use color_eyre::{
     eyre::{eyre, Result},
     Report,
@@ -6,27 +5,27 @@ use color_eyre::{
 
 use std::collections::HashSet;
 
-pub const DAY: Day = Day {
+day! {
     day: 3,
     name: "Perfectly Spherical Houses in a Vacuum",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: aoc_lib::Bench) -> BenchResult {
-    let moves = input.parse::<MoveList>().map_err(UserError)?;
-    b.bench(|| part1(&moves))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let moves = input.parse::<MoveList>()?;
+    Ok(move || part1(&moves))
 }
 
-fn run_part2(input: &str, b: aoc_lib::Bench) -> BenchResult {
-    let moves = input.parse::<MoveList>().map_err(UserError)?;
-    b.bench(|| part2(&moves))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let moves = input.parse::<MoveList>()?;
+    Ok(move || part2(&moves))
 }
 
-struct MoveList(Vec<Move>);
+pub struct MoveList(Vec<Move>);
 
-enum Move {
+pub enum Move {
     North,
     South,
     East,
@@ -50,7 +49,7 @@ impl std::str::FromStr for MoveList {
     }
 }
 
-fn part1(input: &MoveList) -> Result<usize> {
+pub fn part1(input: &MoveList) -> Result<usize> {
     Ok(input
         .0
         .iter()
@@ -69,7 +68,7 @@ fn part1(input: &MoveList) -> Result<usize> {
         .len())
 }
 
-fn part2(input: &MoveList) -> Result<usize> {
+pub fn part2(input: &MoveList) -> Result<usize> {
     let init = [(0, 0), (0, 0)];
     Ok(input
         .0
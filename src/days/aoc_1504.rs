@@ -1,23 +1,23 @@
-use aoc_lib::{Bench, BenchResult, Day};
 use color_eyre::eyre::{eyre, Result};
 
 use std::fmt::Write;
-pub const DAY: Day = Day {
+day! {
     day: 4,
     name: "The Ideal Stocking Stuffer",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part(input, true))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u32> + '_> {
+    Ok(move || part(input, true))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part(input, false))
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u32> + '_> {
+    Ok(move || part(input, false))
 }
 
-fn part(input: &str, is_five: bool) -> Result<u32> {
+pub fn part(input: &str, is_five: bool) -> Result<u32> {
     let mut buf = String::new();
 
     for i in 0..u32::MAX {
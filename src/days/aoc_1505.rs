@@ -1,40 +1,32 @@
-use aoc_lib::{misc::ArrWindows, Bench, BenchResult, Day, NoError};
+use aoc_lib::misc::ArrWindows;
+use color_eyre::eyre::Result;
 
-pub const DAY: Day = Day {
+day! {
     day: 5,
     name: "Doesn't He Have Intern-Elves For This?",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
-
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        Ok::<_, NoError>(
-            input
-                .lines()
-                .map(str::trim)
-                .map(part1)
-                .filter(|i| *i)
-                .count(),
-        )
-    })
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        Ok::<_, NoError>(
-            input
-                .lines()
-                .map(str::trim)
-                .map(part2)
-                .filter(|i| *i)
-                .count(),
-        )
-    })
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(count_nice(input, part1)))
 }
 
-fn part1(input: &str) -> bool {
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(count_nice(input, part2)))
+}
+
+pub fn count_nice(input: &str, is_nice: fn(&str) -> bool) -> usize {
+    input
+        .lines()
+        .map(str::trim)
+        .map(is_nice)
+        .filter(|i| *i)
+        .count()
+}
+
+pub fn part1(input: &str) -> bool {
     let vowels: &[char] = &['a', 'e', 'i', 'o', 'u'];
     let has_three_vowels = input.matches(vowels).count() >= 3;
 
@@ -57,7 +49,7 @@ fn part1(input: &str) -> bool {
     has_three_vowels && !has_invalid_strings && has_double_char
 }
 
-fn part2(input: &str) -> bool {
+pub fn part2(input: &str) -> bool {
     // We know the length is 16 chars.
     let chars = {
         let mut chars = ['\0'; 16];
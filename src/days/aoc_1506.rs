@@ -1,50 +1,47 @@
 #![allow(clippy::unnecessary_wraps)]
 
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use aoc_lib::ParseResult;
 use chumsky::Parser;
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 6,
     name: "Probably a Fire Hazard",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u64>> {
     let instructions: Vec<_> = input
         .lines()
         .map(Instruction::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| part(&instructions, Operation::apply_part1))
+    Ok(move || part(&instructions, Operation::apply_part1))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u64>> {
     let instructions: Vec<_> = input
         .lines()
         .map(Instruction::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| part(&instructions, Operation::apply_part2))
+    Ok(move || part(&instructions, Operation::apply_part2))
 }
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data: Vec<_> = input
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Vec<Instruction>>> + '_> {
+    Ok(move || {
+        let data = input
             .lines()
             .map(Instruction::parse)
             .collect::<Result<_, _>>()?;
-        Ok::<_, Report>(ParseResult(data))
+        Ok(ParseResult(data))
     })
 }
 
 #[derive(Debug, Clone, PartialEq)]
-struct Instruction {
+pub struct Instruction {
     op: Operation,
     top: usize,
     left: usize,
@@ -53,7 +50,7 @@ struct Instruction {
 }
 
 impl Instruction {
-    fn parse(line: &str) -> Result<Instruction> {
+    pub fn parse(line: &str) -> Result<Instruction> {
         fn parser<'a>() -> impl Parser<'a, &'a str, (Operation, (usize, usize), (usize, usize))> {
             use chumsky::{primitive::just, text::int};
 
@@ -87,14 +84,14 @@ impl Instruction {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum Operation {
+pub enum Operation {
     Toggle,
     On,
     Off,
 }
 
 impl Operation {
-    fn apply_part1(self, value: &mut bool) {
+    pub fn apply_part1(self, value: &mut bool) {
         use Operation::*;
         match self {
             Toggle => *value = !*value,
@@ -103,7 +100,7 @@ impl Operation {
         }
     }
 
-    fn apply_part2(self, value: &mut u64) {
+    pub fn apply_part2(self, value: &mut u64) {
         use Operation::*;
         match self {
             Toggle => *value += 2,
@@ -113,7 +110,7 @@ impl Operation {
     }
 }
 
-fn part<T: Copy + Default>(input: &[Instruction], f: fn(Operation, &mut T)) -> Result<u64>
+pub fn part<T: Copy + Default>(input: &[Instruction], f: fn(Operation, &mut T)) -> Result<u64>
 where
     u64: From<T>,
 {
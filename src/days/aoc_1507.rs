@@ -1,47 +1,51 @@
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
-use chumsky::Parser;
+use aoc_lib::ParseResult;
+use chumsky::{
+    error::{Rich, RichReason},
+    extra, Parser,
+};
 use color_eyre::{
     eyre::{eyre, Result},
     Report,
 };
 
-use std::{cell::RefCell, collections::HashMap};
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom, sync::Arc};
 
-pub const DAY: Day = Day {
+day! {
     day: 7,
     name: "Some Assembly Required",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let circuit = Circuit::parse_circuit(input).map_err(UserError)?;
-    b.bench(|| part_1(circuit.clone()))
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
+
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u16>> {
+    let circuit = Circuit::parse_circuit(input)?;
+    Ok(move || part_1(circuit.clone()))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let circuit = Circuit::parse_circuit(input).map_err(UserError)?;
-    b.bench(|| part_2(circuit.clone()))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u16>> {
+    let circuit = Circuit::parse_circuit(input)?;
+    Ok(move || part_2(circuit.clone()))
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = Circuit::parse_circuit(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Circuit>> + '_> {
+    Ok(move || Ok(ParseResult(Circuit::parse_circuit(input)?)))
 }
 
+/// Gate names, which can't also be used to name wires.
+const KEYWORDS: [&str; 5] = ["AND", "OR", "NOT", "LSHIFT", "RSHIFT"];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct WireId(u16);
+pub struct WireId(u16);
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Input {
+pub enum Input {
     Number(u16),
     Wire(WireId),
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Component {
+pub enum Component {
     Const {
         input: Input,
         output: WireId,
@@ -72,12 +76,303 @@ enum Component {
     },
 }
 
+impl Component {
+    pub fn output(self) -> WireId {
+        use Component::*;
+        match self {
+            Const { output, .. }
+            | And { output, .. }
+            | Or { output, .. }
+            | LShift { output, .. }
+            | RShift { output, .. }
+            | Not { output, .. } => output,
+        }
+    }
+
+    fn output_mut(&mut self) -> &mut WireId {
+        use Component::*;
+        match self {
+            Const { output, .. }
+            | And { output, .. }
+            | Or { output, .. }
+            | LShift { output, .. }
+            | RShift { output, .. }
+            | Not { output, .. } => output,
+        }
+    }
+
+    /// The wires this component reads from, ignoring constant inputs.
+    pub fn input_wires(self) -> impl Iterator<Item = WireId> {
+        use Component::*;
+        let (a, b) = match self {
+            Const { input, .. } | Not { input, .. } => (input, None),
+            And {
+                input_a, input_b, ..
+            }
+            | Or {
+                input_a, input_b, ..
+            }
+            | LShift {
+                input_a, input_b, ..
+            }
+            | RShift {
+                input_a, input_b, ..
+            } => (input_a, Some(input_b)),
+        };
+
+        std::iter::once(a).chain(b).filter_map(|input| match input {
+            Input::Wire(id) => Some(id),
+            Input::Number(_) => None,
+        })
+    }
+
+    /// The component's output signal, given a way to look up its inputs.
+    fn compute(self, get: impl Fn(Input) -> Option<u16>) -> Option<u16> {
+        use Component::*;
+        let binary = |a, b, op: fn(u16, u16) -> u16| Some(op(get(a)?, get(b)?));
+
+        match self {
+            Const { input, .. } => get(input),
+            Not { input, .. } => get(input).map(|v| !v),
+            And {
+                input_a, input_b, ..
+            } => binary(input_a, input_b, |a, b| a & b),
+            Or {
+                input_a, input_b, ..
+            } => binary(input_a, input_b, |a, b| a | b),
+            // Shifting a wire by another wire can't be checked when parsing, so shifting
+            // everything out gives 0 rather than panicking.
+            LShift {
+                input_a, input_b, ..
+            } => binary(input_a, input_b, |a, b| {
+                a.checked_shl(b.into()).unwrap_or(0)
+            }),
+            RShift {
+                input_a, input_b, ..
+            } => binary(input_a, input_b, |a, b| {
+                a.checked_shr(b.into()).unwrap_or(0)
+            }),
+        }
+    }
+
+    fn map_inputs(self, f: impl Fn(Input) -> Input) -> Component {
+        use Component::*;
+        match self {
+            Const { input, output } => Const {
+                input: f(input),
+                output,
+            },
+            Not { input, output } => Not {
+                input: f(input),
+                output,
+            },
+            And {
+                input_a,
+                input_b,
+                output,
+            } => And {
+                input_a: f(input_a),
+                input_b: f(input_b),
+                output,
+            },
+            Or {
+                input_a,
+                input_b,
+                output,
+            } => Or {
+                input_a: f(input_a),
+                input_b: f(input_b),
+                output,
+            },
+            LShift {
+                input_a,
+                input_b,
+                output,
+            } => LShift {
+                input_a: f(input_a),
+                input_b: f(input_b),
+                output,
+            },
+            RShift {
+                input_a,
+                input_b,
+                output,
+            } => RShift {
+                input_a: f(input_a),
+                input_b: f(input_b),
+                output,
+            },
+        }
+    }
+
+    /// The gate's name, with any constant operands on the side they're on.
+    fn dot_label(self) -> String {
+        use Component::*;
+        let constant = |input| match input {
+            Input::Number(n) => Some(n.to_string()),
+            Input::Wire(_) => None,
+        };
+
+        let (gate, a, b) = match self {
+            Const { input, .. } => ("CONST", None, constant(input)),
+            Not { input, .. } => ("NOT", None, constant(input)),
+            And {
+                input_a, input_b, ..
+            } => ("AND", constant(input_a), constant(input_b)),
+            Or {
+                input_a, input_b, ..
+            } => ("OR", constant(input_a), constant(input_b)),
+            LShift {
+                input_a, input_b, ..
+            } => ("LSHIFT", constant(input_a), constant(input_b)),
+            RShift {
+                input_a, input_b, ..
+            } => ("RSHIFT", constant(input_a), constant(input_b)),
+        };
+
+        a.into_iter()
+            .chain(Some(gate.to_owned()))
+            .chain(b)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
-struct Circuit {
+pub struct Circuit {
     wires: Vec<Option<u16>>,
+    /// Signals forced onto wires, regardless of what drives them.
+    overrides: Vec<Option<u16>>,
+    wire_ids: Arc<HashMap<String, WireId>>,
     components: Vec<Component>,
-    a_id: WireId,
-    b_id: WireId,
+    /// Indices into `components`, ordered so that every component comes after the
+    /// components driving its inputs.
+    order: Vec<usize>,
+}
+
+/// Checks that every wire is driven exactly once, and that no gate shifts by a constant
+/// of 16 or more. `line_nos` holds the source line of each component.
+fn validate(components: &[Component], line_nos: &[usize], names: &[&str]) -> Result<()> {
+    let mut errors = Vec::new();
+    let mut driven_on = vec![None; names.len()];
+    let mut first_read_on = vec![None; names.len()];
+
+    for (&comp, &line_no) in components.iter().zip(line_nos) {
+        let output = comp.output().0 as usize;
+        match driven_on[output] {
+            Some(first) => errors.push((
+                line_no,
+                format!(
+                    "wire `{}` is already driven on line {}",
+                    names[output], first
+                ),
+            )),
+            None => driven_on[output] = Some(line_no),
+        }
+
+        for wire in comp.input_wires() {
+            first_read_on[wire.0 as usize].get_or_insert(line_no);
+        }
+
+        if let Component::LShift {
+            input_b: Input::Number(shift),
+            ..
+        }
+        | Component::RShift {
+            input_b: Input::Number(shift),
+            ..
+        } = comp
+        {
+            if shift >= 16 {
+                errors.push((line_no, format!("shift by {} is 16 or more", shift)));
+            }
+        }
+    }
+
+    for (id, name) in names.iter().enumerate() {
+        if let (None, Some(line_no)) = (driven_on[id], first_read_on[id]) {
+            errors.push((line_no, format!("wire `{}` is never driven", name)));
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    errors.sort();
+    let errors: Vec<_> = errors
+        .into_iter()
+        .map(|(line_no, error)| format!("line {}: {}", line_no, error))
+        .collect();
+
+    Err(eyre!("Invalid circuit:\n{}", errors.join("\n")))
+}
+
+/// Orders the components so each one only depends on components before it, or reports
+/// the wires in one of the loops preventing that.
+fn sort_components(components: &[Component], names: &[&str]) -> Result<Vec<usize>> {
+    let mut drivers = vec![Vec::new(); names.len()];
+    for (i, comp) in components.iter().enumerate() {
+        drivers[comp.output().0 as usize].push(i);
+    }
+
+    let mut dependents = vec![Vec::new(); components.len()];
+    let mut pending = vec![0; components.len()];
+    for (i, comp) in components.iter().enumerate() {
+        for wire in comp.input_wires() {
+            for &driver in &drivers[wire.0 as usize] {
+                dependents[driver].push(i);
+                pending[i] += 1;
+            }
+        }
+    }
+
+    let mut ready: Vec<_> = (0..components.len()).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(components.len());
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for &dependent in &dependents[i] {
+            pending[dependent] -= 1;
+            if pending[dependent] == 0 {
+                ready.push(dependent);
+            }
+        }
+    }
+
+    if order.len() == components.len() {
+        return Ok(order);
+    }
+
+    // Everything left is either in a loop or downstream of one. Walking backwards through
+    // unsorted drivers from any of them has to end up going round a loop.
+    let mut path = Vec::new();
+    let mut cur = pending.iter().position(|&p| p > 0).unwrap();
+    let loop_start = loop {
+        if let Some(pos) = path.iter().position(|&c| c == cur) {
+            break pos;
+        }
+        path.push(cur);
+
+        cur = components[cur]
+            .input_wires()
+            .flat_map(|wire| &drivers[wire.0 as usize])
+            .copied()
+            .find(|&driver| pending[driver] > 0)
+            .unwrap();
+    };
+
+    let mut wires: Vec<_> = path[loop_start..]
+        .iter()
+        .rev()
+        .map(|&c| names[components[c].output().0 as usize])
+        .collect();
+
+    // Start from the first name so the same loop is always reported the same way.
+    let first = (0..wires.len()).min_by_key(|&i| wires[i]).unwrap();
+    wires.rotate_left(first);
+    wires.push(wires[0]);
+
+    Err(eyre!("Circuit contains a loop: {}", wires.join(" -> ")))
 }
 
 impl Circuit {
@@ -88,121 +383,332 @@ impl Circuit {
         }
     }
 
-    fn evaluate(&mut self, mut exit: impl FnMut(&Self) -> bool) {
-        while exit(self) {
-            for &comp in &self.components {
-                use Component::*;
-                match comp {
-                    Const { input, output } => {
-                        if let (Some(val), output @ None) =
-                            (self.get_wire_val(input), &mut self.wires[output.0 as usize])
-                        {
-                            *output = Some(val);
-                        }
-                    }
+    /// The id of the wire called `name`.
+    pub fn wire_id(&self, name: &str) -> Result<WireId> {
+        self.wire_ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| eyre!("Unknown wire `{}`", name))
+    }
 
-                    Not { input, output } => {
-                        if let (Some(val), output @ None) =
-                            (self.get_wire_val(input), &mut self.wires[output.0 as usize])
-                        {
-                            *output = Some(!val);
-                        }
-                    }
+    /// The name of the wire with the given id.
+    pub fn wire_name(&self, id: WireId) -> Option<&str> {
+        self.wire_ids
+            .iter()
+            .find(|(_, &wire)| wire == id)
+            .map(|(name, _)| name.as_str())
+    }
 
-                    And {
-                        input_a,
-                        input_b,
-                        output,
-                    } => {
-                        if let (Some(a), Some(b), output @ None) = (
-                            self.get_wire_val(input_a),
-                            self.get_wire_val(input_b),
-                            &mut self.wires[output.0 as usize],
-                        ) {
-                            *output = Some(a & b);
-                        }
-                    }
+    /// Every component in the circuit.
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
 
-                    Or {
-                        input_a,
-                        input_b,
-                        output,
-                    } => {
-                        if let (Some(a), Some(b), output @ None) = (
-                            self.get_wire_val(input_a),
-                            self.get_wire_val(input_b),
-                            &mut self.wires[output.0 as usize],
-                        ) {
-                            *output = Some(a | b);
-                        }
-                    }
+    /// The signal on `name` as of the last evaluation.
+    pub fn signal(&self, name: &str) -> Result<u16> {
+        let id = self.wire_id(name)?;
+        self.wires[id.0 as usize].ok_or_else(|| eyre!("Wire `{}` has no signal", name))
+    }
 
-                    LShift {
-                        input_a,
-                        input_b,
-                        output,
-                    } => {
-                        if let (Some(a), Some(b), output @ None) = (
-                            self.get_wire_val(input_a),
-                            self.get_wire_val(input_b),
-                            &mut self.wires[output.0 as usize],
-                        ) {
-                            *output = Some(a << b);
-                        }
-                    }
+    /// Every wire and its signal as of the last evaluation, sorted by name.
+    pub fn signals(&self) -> Vec<(&str, Option<u16>)> {
+        let mut signals: Vec<_> = self
+            .wire_ids
+            .iter()
+            .map(|(name, id)| (name.as_str(), self.wires[id.0 as usize]))
+            .collect();
+        signals.sort_unstable();
+        signals
+    }
 
-                    RShift {
-                        input_a,
-                        input_b,
+    /// Lists every wire's signal like the puzzle does, one `name: signal` per line. Wires
+    /// without a signal are shown as `-`.
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        for (name, signal) in self.signals() {
+            match signal {
+                Some(signal) => dump += &format!("{}: {}\n", name, signal),
+                None => dump += &format!("{}: -\n", name),
+            }
+        }
+        dump
+    }
+
+    /// Renders the circuit as a Graphviz DOT graph, for viewing with something like
+    /// `dot -Tsvg day7.dot -o day7.svg`.
+    ///
+    /// Each component is a gate node, and each wire is an edge from its driver to every
+    /// gate reading it, labelled with the wire's name and its signal as of the last
+    /// evaluation. Wires without a signal are dashed. Undriven and unread wires get a
+    /// plain node at their loose end.
+    pub fn to_dot(&self) -> String {
+        let mut names = vec![""; self.wires.len()];
+        for (name, id) in self.wire_ids.iter() {
+            names[id.0 as usize] = name.as_str();
+        }
+
+        let mut drivers = vec![Vec::new(); self.wires.len()];
+        let mut readers = vec![Vec::new(); self.wires.len()];
+        for (i, comp) in self.components.iter().enumerate() {
+            drivers[comp.output().0 as usize].push(format!("g{}", i));
+            for wire in comp.input_wires() {
+                readers[wire.0 as usize].push(format!("g{}", i));
+            }
+        }
+
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+        for (i, comp) in self.components.iter().enumerate() {
+            dot += &format!("    g{} [shape=box, label=\"{}\"];\n", i, comp.dot_label());
+        }
+
+        for (id, (name, signal)) in names.iter().zip(&self.wires).enumerate() {
+            if drivers[id].is_empty() {
+                dot += &format!("    i{} [shape=plaintext, label=\"{}\"];\n", id, name);
+                drivers[id].push(format!("i{}", id));
+            }
+            if readers[id].is_empty() {
+                dot += &format!("    o{} [shape=plaintext, label=\"{}\"];\n", id, name);
+                readers[id].push(format!("o{}", id));
+            }
+
+            let attrs = match signal {
+                Some(signal) => format!("label=\"{} = {}\"", name, signal),
+                None => format!("label=\"{}\", style=dashed", name),
+            };
+
+            for driver in &drivers[id] {
+                for reader in &readers[id] {
+                    dot += &format!("    {} -> {} [{}];\n", driver, reader, attrs);
+                }
+            }
+        }
+
+        dot += "}\n";
+        dot
+    }
+
+    /// Forces `name` to carry `value` in every later evaluation, ignoring its driver.
+    pub fn override_wire(&mut self, name: &str, value: u16) -> Result<()> {
+        let id = self.wire_id(name)?;
+        self.overrides[id.0 as usize] = Some(value);
+        Ok(())
+    }
+
+    /// Evaluates the circuit from scratch, starting from only the overridden wires. Wires
+    /// with no driver, or fed from one, are left without a signal.
+    pub fn evaluate(&mut self) {
+        self.wires.copy_from_slice(&self.overrides);
+
+        for &idx in &self.order {
+            let comp = self.components[idx];
+            if self.wires[comp.output().0 as usize].is_some() {
+                continue;
+            }
+
+            let value = comp.compute(|input| self.get_wire_val(input));
+            self.wires[comp.output().0 as usize] = value;
+        }
+    }
+
+    /// Shrinks the circuit to what's needed to compute `output`, returning how many
+    /// components were removed.
+    ///
+    /// Gates with only constant inputs are folded into constants, `NOT NOT` chains and
+    /// plain `x -> y` connections are bypassed, and anything `output` doesn't depend on
+    /// is dropped. Wires listed in `free`, and wires that are currently overridden, are
+    /// left alone so that they can still be overridden afterwards. Any other wire may lose
+    /// its signal, and overriding it will no longer affect `output`. Wires left with no
+    /// component driving or reading them are removed from the circuit entirely.
+    pub fn simplify(&mut self, output: &str, free: &[&str]) -> Result<usize> {
+        use Component::*;
+
+        let output = self.wire_id(output)?;
+        let mut is_free: Vec<bool> = self.overrides.iter().map(Option::is_some).collect();
+        for name in free {
+            is_free[self.wire_id(name)?.0 as usize] = true;
+        }
+
+        let mut drivers = vec![Vec::new(); self.wires.len()];
+        for (i, comp) in self.components.iter().enumerate() {
+            drivers[comp.output().0 as usize].push(i);
+        }
+
+        // What reading each wire can be replaced with.
+        let mut replacements: Vec<Option<Input>> = vec![None; self.wires.len()];
+        let mut components = self.components.clone();
+
+        for &idx in &self.order {
+            let comp = components[idx].map_inputs(|input| match input {
+                Input::Wire(id) => replacements[id.0 as usize].unwrap_or(input),
+                Input::Number(_) => input,
+            });
+            components[idx] = comp;
+
+            let out = comp.output().0 as usize;
+            if is_free[out] || drivers[out].len() != 1 {
+                continue;
+            }
+
+            let constant = comp.compute(|input| match input {
+                Input::Number(n) => Some(n),
+                Input::Wire(_) => None,
+            });
+
+            let replacement = match (constant, comp) {
+                (Some(value), _) => {
+                    components[idx] = Const {
+                        input: Input::Number(value),
+                        output: comp.output(),
+                    };
+                    Input::Number(value)
+                }
+                (None, Const { input, .. }) => input,
+                (
+                    None,
+                    Not {
+                        input: Input::Wire(id),
                         output,
-                    } => {
-                        if let (Some(a), Some(b), output @ None) = (
-                            self.get_wire_val(input_a),
-                            self.get_wire_val(input_b),
-                            &mut self.wires[output.0 as usize],
-                        ) {
-                            *output = Some(a >> b);
+                    },
+                ) => match drivers[id.0 as usize][..] {
+                    [driver] if !is_free[id.0 as usize] => match components[driver] {
+                        Not { input, .. } => {
+                            components[idx] = Const { input, output };
+                            input
                         }
-                    }
-                }
+                        _ => continue,
+                    },
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            replacements[out] = Some(replacement);
+        }
+
+        // Walk back from `output` to find everything it depends on.
+        let mut needed = vec![false; components.len()];
+        let mut stack = vec![output];
+        let mut seen = vec![false; self.wires.len()];
+        while let Some(wire) = stack.pop() {
+            if std::mem::replace(&mut seen[wire.0 as usize], true) {
+                continue;
+            }
+
+            for &driver in &drivers[wire.0 as usize] {
+                needed[driver] = true;
+                stack.extend(components[driver].input_wires());
             }
         }
+
+        let removed = needed.iter().filter(|&&n| !n).count();
+        self.components = self
+            .order
+            .iter()
+            .filter(|&&idx| needed[idx])
+            .map(|&idx| components[idx])
+            .collect();
+        self.order = (0..self.components.len()).collect();
+
+        // Drop the wires nothing is connected to any more, renumbering the rest.
+        let mut keep = is_free;
+        for comp in &self.components {
+            keep[comp.output().0 as usize] = true;
+            for wire in comp.input_wires() {
+                keep[wire.0 as usize] = true;
+            }
+        }
+
+        let mut new_ids = vec![WireId(0); keep.len()];
+        let kept = new_ids.iter_mut().zip(&keep).filter(|(_, &k)| k);
+        for (next, (new_id, _)) in kept.enumerate() {
+            *new_id = WireId(next as u16);
+        }
+        let remap = |id: WireId| new_ids[id.0 as usize];
+
+        for comp in &mut self.components {
+            *comp = comp.map_inputs(|input| match input {
+                Input::Wire(id) => Input::Wire(remap(id)),
+                Input::Number(_) => input,
+            });
+            *comp.output_mut() = remap(comp.output());
+        }
+
+        let compact = |values: &[Option<u16>]| -> Vec<Option<u16>> {
+            values
+                .iter()
+                .zip(&keep)
+                .filter(|(_, &k)| k)
+                .map(|(&value, _)| value)
+                .collect()
+        };
+        self.wires = compact(&self.wires);
+        self.overrides = compact(&self.overrides);
+        self.wire_ids = Arc::new(
+            self.wire_ids
+                .iter()
+                .filter(|(_, id)| keep[id.0 as usize])
+                .map(|(name, &id)| (name.clone(), remap(id)))
+                .collect(),
+        );
+
+        Ok(removed)
     }
 
-    fn parse_circuit(input: &str) -> Result<Circuit> {
+    pub fn parse_circuit(input: &str) -> Result<Circuit> {
         let wire_ids = RefCell::new(HashMap::new());
 
-        let get_wire_id = |s: &str| -> WireId {
+        // WireIds are u16, so a circuit can have at most 65,536 distinct wires.
+        let get_wire_id = |s: &str| -> Option<WireId> {
             let mut wire_ids = wire_ids.borrow_mut();
             if let Some(id) = wire_ids.get(s) {
-                return *id;
+                return Some(*id);
             }
 
-            let id = WireId(wire_ids.len() as u16);
+            let id = WireId(u16::try_from(wire_ids.len()).ok()?);
             wire_ids.insert(s.to_owned(), id);
-            id
+            Some(id)
         };
 
         let mut components = Vec::new();
-        let a_id = get_wire_id("a");
-        let b_id = get_wire_id("b");
+        let mut line_nos = Vec::new();
 
-        for line in input.lines().map(str::trim) {
+        for (line_no, line) in input.lines().map(str::trim).enumerate() {
+            let line_no = line_no + 1;
             let (input, output) = line
                 .split_once(" -> ")
-                .ok_or_else(|| eyre!("Unable to parse `{line:?}`"))?;
+                .ok_or_else(|| eyre!("Unable to parse line {}: `{line:?}`", line_no))?;
 
             fn component_parser<'a, 'b: 'a>(
                 output: WireId,
-                id_gen: &'b impl Fn(&'a str) -> WireId,
-            ) -> impl Parser<'a, &'a str, Component> {
+                id_gen: &'b impl Fn(&'a str) -> Option<WireId>,
+            ) -> impl Parser<'a, &'a str, Component, extra::Err<Rich<'a, char>>> {
                 use chumsky::{
                     primitive::just,
                     text::{ident, int},
                 };
 
-                let num_constant = int(10).from_str::<u16>().unwrapped().map(Input::Number);
-                let wire_name = ident().map(id_gen).map(Input::Wire);
+                let num_constant = int(10)
+                    .validate(|s: &str, e, emitter| {
+                        s.parse().unwrap_or_else(|_| {
+                            let msg = format!("constant {} doesn't fit in 16 bits", s);
+                            emitter.emit(Rich::custom(e.span(), msg));
+                            0
+                        })
+                    })
+                    .map(Input::Number);
+                let wire_name = ident()
+                    .validate(move |s: &str, e, emitter| {
+                        if KEYWORDS.contains(&s) {
+                            let msg = format!("`{}` is a keyword, not a wire name", s);
+                            emitter.emit(Rich::custom(e.span(), msg));
+                        }
+                        id_gen(s).unwrap_or_else(|| {
+                            let msg = format!("more than {} wires", u16::MAX as usize + 1);
+                            emitter.emit(Rich::custom(e.span(), msg));
+                            WireId(0)
+                        })
+                    })
+                    .map(Input::Wire);
                 let input = num_constant.or(wire_name);
 
                 let not = just("NOT ")
@@ -250,45 +756,79 @@ impl Circuit {
                     .or(input.map(move |input| Component::Const { input, output }))
             }
 
-            let output_id = get_wire_id(output.trim());
-            let component = component_parser(output_id, &get_wire_id)
+            let output = output.trim();
+            if KEYWORDS.contains(&output) {
+                return Err(eyre!(
+                    "Unable to parse line {}: `{}` is a keyword, not a wire name",
+                    line_no,
+                    output
+                ));
+            }
+            let output_id = get_wire_id(output).ok_or_else(|| {
+                eyre!(
+                    "Unable to parse line {}: more than {} wires",
+                    line_no,
+                    u16::MAX as usize + 1
+                )
+            })?;
+
+            let (component, errors) = component_parser(output_id, &get_wire_id)
                 .parse(input.trim())
-                .into_output()
-                .ok_or_else(|| eyre!("Unable to parse `{line:?}`"))?;
+                .into_output_errors();
+            let custom = errors.iter().find_map(|err| match err.reason() {
+                RichReason::Custom(msg) => Some(msg),
+                _ => None,
+            });
+            if let Some(msg) = custom {
+                return Err(eyre!("Unable to parse line {}: {}", line_no, msg));
+            }
+            let component = component
+                .filter(|_| errors.is_empty())
+                .ok_or_else(|| eyre!("Unable to parse line {}: `{line:?}`", line_no))?;
 
             components.push(component);
+            line_nos.push(line_no);
         }
 
         let wire_ids = wire_ids.into_inner();
+        let mut names = vec![""; wire_ids.len()];
+        for (name, id) in &wire_ids {
+            names[id.0 as usize] = name.as_str();
+        }
+
+        validate(&components, &line_nos, &names)?;
+        let order = sort_components(&components, &names)?;
+
         Ok(Circuit {
             wires: vec![None; wire_ids.len()],
+            overrides: vec![None; wire_ids.len()],
+            wire_ids: Arc::new(wire_ids),
             components,
-            a_id,
-            b_id,
+            order,
         })
     }
 }
 
-fn part_1(mut circuit: Circuit) -> Result<u16> {
-    let mut counter = 0;
-    circuit.evaluate(|c| {
-        counter += 1;
-        c.wires.iter().any(Option::is_none) && counter < 1000
-    });
+impl std::str::FromStr for Circuit {
+    type Err = Report;
 
-    circuit.wires[circuit.a_id.0 as usize].ok_or_else(|| eyre!("Wire not found: a"))
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Circuit::parse_circuit(s)
+    }
 }
 
-fn part_2(mut circuit: Circuit) -> Result<u16> {
-    circuit.wires[circuit.b_id.0 as usize] = Some(46065);
+pub fn part_1(mut circuit: Circuit) -> Result<u16> {
+    circuit.evaluate();
+    circuit.signal("a")
+}
 
-    let mut counter = 0;
-    circuit.evaluate(|c| {
-        counter += 1;
-        c.wires.iter().any(Option::is_none) && counter < 1000
-    });
+pub fn part_2(mut circuit: Circuit) -> Result<u16> {
+    circuit.evaluate();
+    let a = circuit.signal("a")?;
 
-    circuit.wires[circuit.a_id.0 as usize].ok_or_else(|| eyre!("Wire not found: a"))
+    circuit.override_wire("b", a)?;
+    circuit.evaluate();
+    circuit.signal("a")
 }
 
 #[cfg(test)]
@@ -306,27 +846,226 @@ mod tests_1507 {
         NOT x -> h
         NOT y -> i";
 
-        let expected: Vec<Option<u16>> = vec![
-            None,        // a
-            None,        // b
-            Some(123),   // x
-            Some(456),   // y
-            Some(72),    // d
-            Some(507),   // e
-            Some(492),   // f
-            Some(114),   // g
-            Some(65412), // h
-            Some(65079), // i
-        ];
+        let expected = "d: 72
+e: 507
+f: 492
+g: 114
+h: 65412
+i: 65079
+x: 123
+y: 456
+";
 
         let mut circuit = Circuit::parse_circuit(circuit_str).unwrap();
+        circuit.evaluate();
+
+        assert_eq!(expected, circuit.dump());
+        assert_eq!(65412, circuit.signal("h").unwrap());
+        assert!(circuit.signal("a").is_err());
+    }
+
+    #[test]
+    fn dot_test() {
+        let mut circuit = Circuit::parse_circuit("123 -> x\nx LSHIFT 2 -> a").unwrap();
+
+        let expected = "digraph circuit {
+    rankdir=LR;
+    g0 [shape=box, label=\"CONST 123\"];
+    g1 [shape=box, label=\"LSHIFT 2\"];
+    g0 -> g1 [label=\"x\", style=dashed];
+    o1 [shape=plaintext, label=\"a\"];
+    g1 -> o1 [label=\"a\", style=dashed];
+}
+";
+        assert_eq!(expected, circuit.to_dot());
+
+        circuit.evaluate();
+        let expected = expected
+            .replace("\"x\", style=dashed", "\"x = 123\"")
+            .replace("\"a\", style=dashed", "\"a = 492\"");
+        assert_eq!(expected, circuit.to_dot());
+    }
+
+    #[test]
+    fn simplify_test() {
+        let circuit_str = "123 -> x
+        x AND 7 -> d
+        NOT b -> e
+        NOT e -> f
+        f OR d -> a
+        456 -> b
+        b RSHIFT 2 -> g";
+
+        let original = Circuit::parse_circuit(circuit_str).unwrap();
+
+        // Only `456 -> b` and `b OR 3 -> a` are left.
+        let mut circuit = original.clone();
+        assert_eq!(5, circuit.simplify("a", &["b"]).unwrap());
+        assert_eq!(
+            part_1(original.clone()).unwrap(),
+            part_1(circuit.clone()).unwrap()
+        );
+        assert_eq!(part_2(original.clone()).unwrap(), part_2(circuit).unwrap());
+
+        // Only the wires still connected to something are left.
+        let mut circuit = original.clone();
+        circuit.simplify("a", &["b"]).unwrap();
+        circuit.evaluate();
+        assert_eq!("a: 459\nb: 456\n", circuit.dump());
+
+        // Without `b` being free, `a` is a constant.
+        let mut circuit = original.clone();
+        assert_eq!(6, circuit.simplify("a", &[]).unwrap());
+        assert_eq!(part_1(original).unwrap(), part_1(circuit.clone()).unwrap());
+
+        circuit.evaluate();
+        assert_eq!("a: 459\n", circuit.dump());
+        assert!(circuit.wire_id("b").is_err());
+        assert_eq!(
+            "digraph circuit {
+    rankdir=LR;
+    g0 [shape=box, label=\"CONST 459\"];
+    o0 [shape=plaintext, label=\"a\"];
+    g0 -> o0 [label=\"a = 459\"];
+}
+",
+            circuit.to_dot()
+        );
+    }
+
+    #[test]
+    fn part2_test() {
+        let circuit_str = "123 -> b
+        b AND 7 -> c
+        c LSHIFT 1 -> a";
+
+        let circuit = Circuit::parse_circuit(circuit_str).unwrap();
+
+        assert_eq!(6, part_1(circuit.clone()).unwrap());
+        // b is overridden with 6, so c = 6 and a = 12.
+        assert_eq!(12, part_2(circuit).unwrap());
+    }
+
+    #[test]
+    fn override_test() {
+        let mut circuit = Circuit::parse_circuit("123 -> x\nx OR 1 -> a").unwrap();
+
+        circuit.override_wire("x", 4).unwrap();
+        assert_eq!(5, part_1(circuit.clone()).unwrap());
+
+        assert!(circuit.override_wire("y", 4).is_err());
+    }
+
+    #[test]
+    fn send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Circuit>();
+    }
+
+    #[test]
+    fn accessors_test() {
+        let circuit: Circuit = "123 -> x\nx OR 1 -> a".parse().unwrap();
+
+        let x = circuit.wire_id("x").unwrap();
+        assert_eq!(Some("x"), circuit.wire_name(x));
+        assert_eq!(circuit.components().len(), 2);
+        assert_eq!(circuit.components()[0].output(), x);
+    }
 
-        let mut counter = 0;
-        circuit.evaluate(|c| {
-            counter += 1;
-            c.wires.iter().any(Option::is_none) && counter < 100
-        });
+    #[test]
+    fn reverse_order_test() {
+        // Every gate is listed before the gate driving it.
+        let circuit_str = "b RSHIFT 1 -> a
+        c LSHIFT 2 -> b
+        NOT d -> c
+        e AND 255 -> d
+        65535 -> e";
+
+        let circuit = Circuit::parse_circuit(circuit_str).unwrap();
+
+        assert_eq!(32256, part_1(circuit).unwrap());
+    }
+
+    #[test]
+    fn validate_test() {
+        let circuit_str = "123 -> x
+        x LSHIFT 16 -> y
+        NOT z -> a
+        456 -> x";
+
+        let err = Circuit::parse_circuit(circuit_str).unwrap_err();
+
+        assert_eq!(
+            "Invalid circuit:
+line 2: shift by 16 is 16 or more
+line 3: wire `z` is never driven
+line 4: wire `x` is already driven on line 1",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn loop_test() {
+        let circuit_str = "x AND y -> a
+        z -> x
+        NOT x -> z
+        5 -> y";
+
+        let err = Circuit::parse_circuit(circuit_str).unwrap_err();
+
+        assert_eq!("Circuit contains a loop: x -> z -> x", err.to_string());
+    }
+
+    #[test]
+    fn constant_range_test() {
+        let err = Circuit::parse_circuit("70000 -> a").unwrap_err();
+        assert_eq!(
+            "Unable to parse line 1: constant 70000 doesn't fit in 16 bits",
+            err.to_string()
+        );
+
+        let err = Circuit::parse_circuit("1 -> x\nx LSHIFT 99999 -> a").unwrap_err();
+        assert_eq!(
+            "Unable to parse line 2: constant 99999 doesn't fit in 16 bits",
+            err.to_string()
+        );
+
+        let mut circuit = Circuit::parse_circuit("65535 -> a").unwrap();
+        circuit.evaluate();
+        assert_eq!(65535, circuit.signal("a").unwrap());
+    }
+
+    #[test]
+    fn keyword_wire_test() {
+        let err = Circuit::parse_circuit("1 -> AND").unwrap_err();
+        assert_eq!(
+            "Unable to parse line 1: `AND` is a keyword, not a wire name",
+            err.to_string()
+        );
+
+        let err = Circuit::parse_circuit("1 -> x\nx OR RSHIFT -> a").unwrap_err();
+        assert_eq!(
+            "Unable to parse line 2: `RSHIFT` is a keyword, not a wire name",
+            err.to_string()
+        );
+
+        let err = Circuit::parse_circuit("NOT -> a").unwrap_err();
+        assert_eq!(
+            "Unable to parse line 1: `NOT` is a keyword, not a wire name",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn wire_limit_test() {
+        let mut circuit_str: String = (0..65536).map(|i| format!("1 -> w{}\n", i)).collect();
+        assert!(Circuit::parse_circuit(&circuit_str).is_ok());
 
-        assert_eq!(expected, circuit.wires);
+        circuit_str.push_str("1 -> one_too_many");
+        let err = Circuit::parse_circuit(&circuit_str).unwrap_err();
+        assert_eq!(
+            "Unable to parse line 65537: more than 65536 wires",
+            err.to_string()
+        );
     }
 }
@@ -1,40 +1,42 @@
-use aoc_lib::{Bench, BenchResult, Day, NoError};
+use color_eyre::eyre::Result;
 
-pub const DAY: Day = Day {
+day! {
     day: 8,
     name: "Matchsticks",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
-
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        Ok::<usize, NoError>(
-            input
-                .lines()
-                .map(str::trim)
-                .map(part1)
-                .map(|(code, rendered)| code - rendered)
-                .sum(),
-        )
-    })
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        Ok::<usize, NoError>(
-            input
-                .lines()
-                .map(str::trim)
-                .map(part2)
-                .map(|(code, rendered)| rendered - code)
-                .sum(),
-        )
-    })
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(decoded_overhead(input)))
 }
 
-fn part1(input: &str) -> (usize, usize) {
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(encoded_overhead(input)))
+}
+
+/// Characters of code minus characters in memory, summed over every line.
+pub fn decoded_overhead(input: &str) -> usize {
+    input
+        .lines()
+        .map(str::trim)
+        .map(part1)
+        .map(|(code, rendered)| code - rendered)
+        .sum()
+}
+
+/// Characters of the re-encoded string minus characters of code, summed over every line.
+pub fn encoded_overhead(input: &str) -> usize {
+    input
+        .lines()
+        .map(str::trim)
+        .map(part2)
+        .map(|(code, rendered)| rendered - code)
+        .sum()
+}
+
+pub fn part1(input: &str) -> (usize, usize) {
     let mut rendered = String::with_capacity(input.len());
 
     let mut input_rem = input;
@@ -80,7 +82,7 @@ fn part1(input: &str) -> (usize, usize) {
     (input.len(), rendered.chars().count())
 }
 
-fn part2(input: &str) -> (usize, usize) {
+pub fn part2(input: &str) -> (usize, usize) {
     let mut rendered = String::new();
     rendered.push('"');
 
@@ -1,41 +1,111 @@
-use aoc_lib::{misc::ArrWindows, Bench, BenchResult, Day, ParseResult, UserError};
+use aoc_lib::{misc::ArrWindows, ParseResult};
 use chumsky::Parser;
 use color_eyre::eyre::eyre;
-use color_eyre::{eyre::Result, Report};
+use color_eyre::eyre::Result;
 use itertools::Itertools;
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-pub const DAY: Day = Day {
+day! {
     day: 9,
     name: "All in a Single Night",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let map = Map::parse(input).map_err(UserError)?;
-    b.bench(|| Map::shortest(&map))
+    part_1: solve_part1,
+    part_2: solve_part2,
+    answer: route_distance,
+    other: [
+        ("Parse", solve_parse),
+        ("Shortest (Brute Force)", solve_shortest_brute_force),
+        ("Longest (Brute Force)", solve_longest_brute_force),
+        ("Shortest Tour", solve_shortest_tour),
+        ("Longest Tour", solve_longest_tour),
+    ],
+}
+
+pub fn solve_part1<'a>(input: &'a str) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.shortest())
+}
+
+pub fn solve_part2<'a>(input: &'a str) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.longest())
+}
+
+pub fn solve_shortest_brute_force<'a>(
+    input: &'a str,
+) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.shortest_brute_force())
+}
+
+pub fn solve_longest_brute_force<'a>(
+    input: &'a str,
+) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.longest_brute_force())
+}
+
+pub fn solve_shortest_tour<'a>(input: &'a str) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.shortest_tour())
+}
+
+pub fn solve_longest_tour<'a>(input: &'a str) -> Result<impl Fn() -> Result<Route<'a>> + 'a> {
+    let map = Map::parse(input)?;
+    Ok(move || map.longest_tour())
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let map = Map::parse(input).map_err(UserError)?;
-    b.bench(|| Map::longest(&map))
+
+pub fn solve_parse<'a>(input: &'a str) -> Result<impl Fn() -> Result<ParseResult<Map<'a>>> + 'a> {
+    Ok(move || Ok(ParseResult(Map::parse(input)?)))
 }
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = Map::parse(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+
+/// A trip through every location, with the distance of each leg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route<'a> {
+    pub stops: Vec<&'a str>,
+    pub legs: Vec<u32>,
+    pub total: u32,
+}
+
+/// Shown as the stops in order, followed by the total: `London -> Dublin -> Belfast (605)`.
+impl fmt::Display for Route<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.stops.join(" -> "), self.total)
+    }
+}
+
+/// Picks the distance out of a printed route for checking against `answers.txt`, as
+/// there may be more than one route with the same distance.
+pub fn route_distance(answer: &str) -> &str {
+    answer
+        .strip_suffix(')')
+        .and_then(|a| a.rsplit_once(" ("))
+        .map_or(answer, |(_, distance)| distance)
+}
+
+/// The most locations `Map::best_route` will take. Its table has an entry for every
+/// subset of locations and every location in it, so it grows as `n * 2^n`: 16 locations
+/// take about 16 MiB, and every location past that doubles it.
+pub const MAX_LOCATIONS: usize = 16;
+
+/// Whether a route ends wherever it runs out of locations, or returns to where it started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trip {
+    Path,
+    Tour,
 }
 
 #[derive(Debug, PartialEq)]
-struct Map<'a> {
+pub struct Map<'a> {
     distances: HashMap<(&'a str, &'a str), u32>,
     locations: HashSet<&'a str>,
 }
 
 impl<'a> Map<'a> {
-    fn parse(input: &str) -> Result<Map> {
+    pub fn parse(input: &str) -> Result<Map> {
         let mut locations = HashSet::new();
         let mut distances = HashMap::new();
 
@@ -74,32 +144,188 @@ impl<'a> Map<'a> {
         })
     }
 
-    fn shortest(&self) -> Result<u32> {
-        let mut min_distance = u32::MAX;
+    /// The distance between two locations, or `None` if there's no way between them.
+    fn distance(&self, a: &str, b: &str) -> Option<u32> {
+        if a == b {
+            Some(0)
+        } else {
+            self.distances.get(&(a, b)).copied()
+        }
+    }
 
-        for route in self.locations.iter().permutations(self.locations.len()) {
-            let route_distance = ArrWindows::new(&route)
-                .map(|&[a, b]| self.distances[&(*a, *b)])
-                .sum();
+    /// The locations in a fixed order, and the distances between them indexed the same way.
+    fn distance_matrix(&self) -> (Vec<&'a str>, Vec<Vec<Option<u32>>>) {
+        let mut locations: Vec<_> = self.locations.iter().copied().collect();
+        locations.sort_unstable();
+
+        let matrix = locations
+            .iter()
+            .map(|&a| locations.iter().map(|&b| self.distance(a, b)).collect())
+            .collect();
+
+        (locations, matrix)
+    }
+
+    /// Builds the route through `stops`, which must all be connected.
+    fn route(&self, stops: Vec<&'a str>) -> Route<'a> {
+        let legs: Vec<u32> = ArrWindows::new(&stops)
+            .map(|&[a, b]| self.distance(a, b).unwrap())
+            .collect();
+
+        Route {
+            total: legs.iter().sum(),
+            stops,
+            legs,
+        }
+    }
+
+    /// Finds the best route visiting every location once with the Held-Karp algorithm,
+    /// where `better(a, b)` says whether distance `a` is preferable to `b`. Locations with
+    /// no distance between them can't be travelled between directly.
+    fn best_route(&self, trip: Trip, better: fn(u32, u32) -> bool) -> Result<Route<'a>> {
+        let (locations, distances) = self.distance_matrix();
+        let n = locations.len();
+        if n == 0 {
+            return Err(eyre!("No locations to visit"));
+        }
+        if n > MAX_LOCATIONS {
+            return Err(eyre!(
+                "Too many locations: {}, the most supported is {}",
+                n,
+                MAX_LOCATIONS
+            ));
+        }
+
+        // A tour visits every location, so it may as well start from the first.
+        let starts = match trip {
+            Trip::Path => n,
+            Trip::Tour => 1,
+        };
+
+        // best[visited * n + last] is the best distance of a route through the set of
+        // locations in `visited`, ending at `last`, along with the location before `last`.
+        // Routes of a single location are their own predecessor.
+        let full = 1 << n;
+        let mut best: Vec<Option<(u32, usize)>> = vec![None; full * n];
+        for start in 0..starts {
+            best[(1 << start) * n + start] = Some((0, start));
+        }
+
+        for visited in 1..full {
+            for last in 0..n {
+                let distance = match best[visited * n + last] {
+                    Some((distance, _)) => distance,
+                    None => continue,
+                };
+
+                for next in (0..n).filter(|next| visited & (1 << next) == 0) {
+                    let leg = match distances[last][next] {
+                        Some(leg) => leg,
+                        None => continue,
+                    };
+
+                    let distance = distance + leg;
+                    let slot = &mut best[(visited | 1 << next) * n + next];
+                    if slot.is_none_or(|(cur, _)| better(distance, cur)) {
+                        *slot = Some((distance, last));
+                    }
+                }
+            }
+        }
+
+        let closing_leg = |last: usize| match trip {
+            Trip::Path => Some(0),
+            Trip::Tour => distances[last][0],
+        };
+
+        let (mut last, _) = best[(full - 1) * n..]
+            .iter()
+            .enumerate()
+            .filter_map(|(last, end)| Some((last, end.as_ref()?.0 + closing_leg(last)?)))
+            .reduce(|a, b| if better(b.1, a.1) { b } else { a })
+            .ok_or_else(|| eyre!("No route visits every location"))?;
+
+        // Walk back through the predecessors to recover the route.
+        let mut visited = full - 1;
+        let mut stops = vec![locations[last]];
+        while visited != 1 << last {
+            let (_, prev) = best[visited * n + last].unwrap();
+            visited &= !(1 << last);
+            last = prev;
+            stops.push(locations[last]);
+        }
+        stops.reverse();
 
-            min_distance = min_distance.min(route_distance);
+        if trip == Trip::Tour {
+            stops.push(stops[0]);
         }
 
-        Ok(min_distance)
+        Ok(self.route(stops))
+    }
+
+    pub fn shortest(&self) -> Result<Route<'a>> {
+        self.best_route(Trip::Path, |a, b| a < b)
+    }
+
+    pub fn longest(&self) -> Result<Route<'a>> {
+        self.best_route(Trip::Path, |a, b| a > b)
+    }
+
+    /// The shortest route that returns to where it started.
+    pub fn shortest_tour(&self) -> Result<Route<'a>> {
+        self.best_route(Trip::Tour, |a, b| a < b)
     }
 
-    fn longest(&self) -> Result<u32> {
-        let mut max_distance = 0;
+    /// The longest route that returns to where it started.
+    pub fn longest_tour(&self) -> Result<Route<'a>> {
+        self.best_route(Trip::Tour, |a, b| a > b)
+    }
+
+    /// Tries every order of the locations, where `better(a, b)` says whether distance `a`
+    /// is preferable to `b`.
+    fn best_route_brute_force(
+        &self,
+        trip: Trip,
+        better: fn(u32, u32) -> bool,
+    ) -> Result<Route<'a>> {
+        if self.locations.is_empty() {
+            return Err(eyre!("No locations to visit"));
+        }
+
+        let mut best: Option<(u32, Vec<&&str>)> = None;
+
+        for mut route in self.locations.iter().permutations(self.locations.len()) {
+            if trip == Trip::Tour {
+                route.push(route[0]);
+            }
 
-        for route in self.locations.iter().permutations(self.locations.len()) {
             let route_distance = ArrWindows::new(&route)
-                .map(|&[a, b]| self.distances[&(*a, *b)])
-                .sum();
+                .map(|&[a, b]| self.distance(a, b))
+                .sum::<Option<u32>>();
 
-            max_distance = max_distance.max(route_distance);
+            let route_distance = match route_distance {
+                Some(distance) => distance,
+                None => continue,
+            };
+
+            if best
+                .as_ref()
+                .is_none_or(|(distance, _)| better(route_distance, *distance))
+            {
+                best = Some((route_distance, route));
+            }
         }
 
-        Ok(max_distance)
+        let (_, route) = best.ok_or_else(|| eyre!("No route visits every location"))?;
+        Ok(self.route(route.into_iter().copied().collect()))
+    }
+
+    pub fn shortest_brute_force(&self) -> Result<Route<'a>> {
+        self.best_route_brute_force(Trip::Path, |a, b| a < b)
+    }
+
+    pub fn longest_brute_force(&self) -> Result<Route<'a>> {
+        self.best_route_brute_force(Trip::Path, |a, b| a > b)
     }
 }
 
@@ -144,6 +370,110 @@ mod tests_1509 {
 
         let map = Map::parse(input).unwrap();
 
-        assert_eq!(605, map.shortest().unwrap());
+        let route = map.shortest().unwrap();
+        assert_eq!("London -> Dublin -> Belfast (605)", route.to_string());
+        assert_eq!(route.legs, [464, 141]);
+        assert_eq!(605, map.shortest_brute_force().unwrap().total);
+        assert_eq!("605", route_distance(&route.to_string()));
+    }
+
+    #[test]
+    fn too_many_locations_test() {
+        // Every location is 1 away from every other.
+        let input = |n: usize| {
+            (0..n)
+                .tuple_combinations()
+                .map(|(a, b)| format!("L{} to L{} = 1", a, b))
+                .join("\n")
+        };
+
+        let input_16 = input(MAX_LOCATIONS);
+        let map = Map::parse(&input_16).unwrap();
+        assert_eq!(15, map.shortest().unwrap().total);
+
+        let input_17 = input(MAX_LOCATIONS + 1);
+        let map = Map::parse(&input_17).unwrap();
+        let err = map.shortest().unwrap_err();
+        assert_eq!(
+            "Too many locations: 17, the most supported is 16",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn part2_example() {
+        let input = "London to Dublin = 464
+        London to Belfast = 518
+        Dublin to Belfast = 141";
+
+        let map = Map::parse(input).unwrap();
+
+        assert_eq!(982, map.longest().unwrap().total);
+        assert_eq!(982, map.longest_brute_force().unwrap().total);
+    }
+
+    #[test]
+    fn missing_edge_test() {
+        let input = "A to B = 1
+        B to C = 2
+        C to D = 3
+        A to D = 4
+        B to D = 5";
+
+        let map = Map::parse(input).unwrap();
+
+        // Routes can come out in either direction.
+        assert_eq!(6, map.shortest().unwrap().total);
+        assert_eq!(11, map.longest().unwrap().total);
+
+        let tour = map.shortest_tour().unwrap();
+        assert_eq!(10, tour.total);
+        assert_eq!(tour.stops.first(), tour.stops.last());
+
+        // Without A to D, there's no way to get back to A after visiting the rest.
+        let map = Map::parse("A to B = 1\nB to C = 2\nC to D = 3\nB to D = 5").unwrap();
+        assert!(map.shortest().is_ok());
+        assert!(map.shortest_tour().is_err());
+        assert!(map
+            .best_route_brute_force(Trip::Tour, |a, b| a < b)
+            .is_err());
+
+        // A star can't be walked as a path at all.
+        let map = Map::parse("A to B = 1\nA to C = 1\nA to D = 1").unwrap();
+        assert!(map.shortest().is_err());
+        assert!(map.shortest_brute_force().is_err());
+    }
+
+    #[test]
+    fn brute_force_agrees_test() {
+        let names = ["A", "B", "C", "D", "E", "F", "G"];
+        let mut input = String::new();
+        for (i, a) in names.iter().enumerate() {
+            for (j, b) in names.iter().enumerate().skip(i + 1) {
+                let distance = (i * 37 + j * 101) % 89 + 1;
+                input += &format!("{} to {} = {}\n", a, b, distance);
+            }
+        }
+
+        let map = Map::parse(input.trim()).unwrap();
+
+        for (expected, actual) in [
+            (map.shortest_brute_force(), map.shortest()),
+            (map.longest_brute_force(), map.longest()),
+            (
+                map.best_route_brute_force(Trip::Tour, |a, b| a < b),
+                map.shortest_tour(),
+            ),
+            (
+                map.best_route_brute_force(Trip::Tour, |a, b| a > b),
+                map.longest_tour(),
+            ),
+        ] {
+            let (expected, actual) = (expected.unwrap(), actual.unwrap());
+            assert_eq!(expected.total, actual.total);
+            assert_eq!(actual.total, actual.legs.iter().sum::<u32>());
+            assert_eq!(actual.stops.iter().unique().count(), names.len());
+            assert_eq!(actual.legs.len(), actual.stops.len() - 1);
+        }
     }
 }
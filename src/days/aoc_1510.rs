@@ -1,23 +1,24 @@
-use aoc_lib::{Bench, BenchResult, Day, NoError};
+use color_eyre::eyre::Result;
 use itertools::Itertools;
 use itoa::Buffer;
 
-pub const DAY: Day = Day {
+day! {
     day: 10,
     name: "Elves Look, Elves Say",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| Ok::<_, NoError>(looksay(input.to_owned(), 40).len()))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(looksay(input.to_owned(), 40).len()))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| Ok::<_, NoError>(looksay(input.to_owned(), 50).len()))
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    Ok(move || Ok(looksay(input.to_owned(), 50).len()))
 }
 
-fn looksay(input: String, iterations: usize) -> String {
+pub fn looksay(input: String, iterations: usize) -> String {
     let mut buf_a = input;
     let mut buf_b = String::new();
     let mut fmt_buf = Buffer::new();
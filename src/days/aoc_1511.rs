@@ -1,27 +1,27 @@
-use aoc_lib::{misc::ArrWindows, Bench, BenchResult, Day};
+use aoc_lib::misc::ArrWindows;
 use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 11,
     name: "Corporate Policy",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part1_next_password(input))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<String> + '_> {
+    Ok(move || part1_next_password(input))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| part1_next_password(input).and_then(|pswd| part1_next_password(&pswd)))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<String> + '_> {
+    Ok(move || part1_next_password(input).and_then(|pswd| part1_next_password(&pswd)))
 }
 
 fn banned_char(c: char) -> bool {
     c == 'i' || c == 'o' || c == 'l'
 }
 
-fn part1_validity(char_buffer: &[char]) -> bool {
+pub fn part1_validity(char_buffer: &[char]) -> bool {
     let has_banned_letters = char_buffer.iter().any(|&c| banned_char(c));
 
     if has_banned_letters {
@@ -54,7 +54,7 @@ fn part1_validity(char_buffer: &[char]) -> bool {
     seen_pairs >= 2
 }
 
-fn part1_next_password(pswd: &str) -> Result<String> {
+pub fn part1_next_password(pswd: &str) -> Result<String> {
     let max_num = 26u64.pow(9) - 1;
 
     let decoded = pswd
@@ -1,25 +1,25 @@
-use aoc_lib::{Bench, BenchResult, Day, UserError};
 use color_eyre::eyre::{Report, Result};
 use serde_json::Value;
 
-pub const DAY: Day = Day {
+day! {
     day: 12,
     name: "JSAbacusFramework.io",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let values: Value = serde_json::from_str(input).map_err(UserError)?;
-    b.bench(|| part1(&values))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<i64>> {
+    let values: Value = serde_json::from_str(input)?;
+    Ok(move || part1(&values))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let values: Value = serde_json::from_str(input).map_err(UserError)?;
-    b.bench(|| part2(&values))
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<i64>> {
+    let values: Value = serde_json::from_str(input)?;
+    Ok(move || part2(&values))
 }
 
-fn part1(input: &Value) -> Result<i64> {
+pub fn part1(input: &Value) -> Result<i64> {
     Ok(match input {
         Value::Number(n) => n.as_i64().unwrap(),
         Value::Array(arr) => arr
@@ -34,7 +34,7 @@ fn part1(input: &Value) -> Result<i64> {
     })
 }
 
-fn part2(input: &Value) -> Result<i64> {
+pub fn part2(input: &Value) -> Result<i64> {
     Ok(match input {
         Value::Number(n) => n.as_i64().unwrap(),
         Value::Array(arr) => arr
@@ -1,54 +1,64 @@
-use aoc_lib::{misc::ArrWindows, Bench, BenchResult, Day, ParseResult, UserError};
+use aoc_lib::{misc::ArrWindows, ParseResult};
 use chumsky::Parser;
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use color_eyre::eyre::{eyre, Result};
 use itertools::Itertools;
 
 use std::collections::{BTreeSet, HashMap};
 
-pub const DAY: Day = Day {
+day! {
     day: 13,
     name: "Knights of the Dinner Table",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
-
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let mut table = People::parse(input).map_err(UserError)?;
-    table.build_fast_lookup();
-    b.bench(|| table.biggest_happiness())
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [
+        ("Parse", solve_parse),
+        ("Part 1 (Brute Force)", solve_part1_brute_force),
+        ("Part 2 (Brute Force)", solve_part2_brute_force),
+    ],
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let mut table = People::parse(input).map_err(UserError)?;
-    table.people.push("Self");
-    for p in &table.people {
-        table.happiness.insert(("Self", p), 0);
-        table.happiness.insert((p, "Self"), 0);
-    }
-    table.build_fast_lookup();
-    b.bench(|| table.biggest_happiness())
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
+    let table = People::parse(input)?;
+    Ok(move || table.biggest_happiness())
+}
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
+    let mut table = People::parse(input)?;
+    table.add_self();
+    Ok(move || table.biggest_happiness())
+}
+
+pub fn solve_part1_brute_force(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
+    let table = People::parse(input)?;
+    Ok(move || table.biggest_happiness_brute_force())
+}
+
+pub fn solve_part2_brute_force(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
+    let mut table = People::parse(input)?;
+    table.add_self();
+    Ok(move || table.biggest_happiness_brute_force())
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = People::parse(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+pub fn solve_parse<'a>(
+    input: &'a str,
+) -> Result<impl Fn() -> Result<ParseResult<People<'a>>> + 'a> {
+    Ok(move || Ok(ParseResult(People::parse(input)?)))
 }
 
+/// The most guests `People::biggest_happiness` will seat. Its table has an entry for every
+/// subset of the guests after the first and every guest, so it grows as `n * 2^(n - 1)`:
+/// 20 guests take 40 MiB, and every guest past that doubles it.
+pub const MAX_GUESTS: usize = 20;
+
 #[derive(Debug, PartialEq, Clone)]
-struct People<'a> {
+pub struct People<'a> {
     happiness: HashMap<(&'a str, &'a str), i32>,
     people: Vec<&'a str>,
     fast_lookup: Vec<i32>,
 }
 
 impl<'a> People<'a> {
-    fn parse(input: &'a str) -> Result<Self> {
+    pub fn parse(input: &'a str) -> Result<Self> {
         let mut people = BTreeSet::new();
         let mut happiness = HashMap::new();
 
@@ -83,15 +93,35 @@ impl<'a> People<'a> {
             happiness.insert((first, second), dir * mag);
         }
 
-        Ok(People {
+        let mut table = People {
             people: people.into_iter().collect(),
             happiness,
             fast_lookup: Vec::new(),
-        })
+        };
+        table.build_fast_lookup()?;
+
+        Ok(table)
+    }
+
+    /// Adds a neutral guest who neither gains nor loses happiness next to anyone.
+    pub fn add_self(&mut self) {
+        self.people.push("Self");
+        for p in &self.people {
+            self.happiness.insert(("Self", p), 0);
+            self.happiness.insert((p, "Self"), 0);
+        }
+
+        // Copy the existing pairs over, leaving everyone next to Self at 0.
+        let n = self.people.len();
+        let mut fast_lookup = vec![0; n * n];
+        for (a, b) in (0..n - 1).cartesian_product(0..n - 1) {
+            fast_lookup[a * n + b] = self.fast_lookup[a * (n - 1) + b];
+        }
+        self.fast_lookup = fast_lookup;
     }
 
     // Saves us doing the hash lookup later.
-    fn build_fast_lookup(&mut self) {
+    fn build_fast_lookup(&mut self) -> Result<()> {
         self.fast_lookup
             .resize(self.people.len() * self.people.len(), 0);
 
@@ -101,15 +131,73 @@ impl<'a> People<'a> {
             let b_name = self.people[b];
 
             if a != b {
-                let a_val = self.happiness[&(a_name, b_name)];
-                let b_val = self.happiness[&(b_name, a_name)];
+                let a_val = self.happiness.get(&(a_name, b_name)).ok_or_else(|| {
+                    eyre!(
+                        "No happiness given for {} sitting next to {}",
+                        a_name,
+                        b_name
+                    )
+                })?;
 
-                self.fast_lookup[a * self.people.len() + b] = a_val + b_val;
+                self.fast_lookup[a * self.people.len() + b] += a_val;
+                self.fast_lookup[b * self.people.len() + a] += a_val;
             }
         }
+
+        Ok(())
     }
 
-    fn biggest_happiness(&self) -> Result<i32> {
+    /// Finds the happiest seating with dynamic programming over subsets of guests. The
+    /// table is round, so any seating can be rotated to put the first guest in a fixed
+    /// seat, leaving the rest to be added one at a time on their right.
+    pub fn biggest_happiness(&self) -> Result<i32> {
+        let n = self.people.len();
+        if n < 2 {
+            // Nobody to sit next to.
+            return Ok(0);
+        }
+        if n > MAX_GUESTS {
+            return Err(eyre!(
+                "Too many guests: {}, the most supported is {}",
+                n,
+                MAX_GUESTS
+            ));
+        }
+
+        // The first guest is always seated, so guest `i` is tracked in bit `i - 1`.
+        // best[seated * n + last] is the happiest line of guests starting from the first,
+        // seating everyone in `seated` and ending with `last`.
+        let full = 1 << (n - 1);
+        let mut best = vec![i32::MIN; full * n];
+        for second in 1..n {
+            best[(1 << (second - 1)) * n + second] = self.fast_lookup[second];
+        }
+
+        for seated in 1..full {
+            for last in 1..n {
+                let happiness = best[seated * n + last];
+                if happiness == i32::MIN {
+                    continue;
+                }
+
+                for next in (1..n).filter(|next| seated & (1 << (next - 1)) == 0) {
+                    let slot = &mut best[(seated | 1 << (next - 1)) * n + next];
+                    *slot = (*slot).max(happiness + self.fast_lookup[last * n + next]);
+                }
+            }
+        }
+
+        // Close the circle by sitting the last guest next to the first.
+        let max_change = (1..n)
+            .map(|last| best[(full - 1) * n + last] + self.fast_lookup[last * n])
+            .max()
+            .unwrap_or(0);
+
+        Ok(max_change)
+    }
+
+    /// Tries every seating, including all the rotations of each.
+    pub fn biggest_happiness_brute_force(&self) -> Result<i32> {
         let mut max_change = 0;
 
         let range = 0..self.people.len();
@@ -166,7 +254,13 @@ mod tests_1513 {
                 map.insert(("David", "Carol"), 41);
                 map
             },
-            fast_lookup: Vec::new(),
+            #[rustfmt::skip]
+            fast_lookup: vec![
+                0, 137, -141, 44,
+                137, 0, 53, -70,
+                -141, 53, 0, 96,
+                44, -70, 96, 0,
+            ],
         };
 
         let actual = People::parse(input).unwrap();
@@ -189,10 +283,116 @@ mod tests_1513 {
         David would lose 7 happiness units by sitting next to Bob.
         David would gain 41 happiness units by sitting next to Carol.";
 
-        let mut table = People::parse(input).unwrap();
-        table.build_fast_lookup();
+        let table = People::parse(input).unwrap();
         let actual = table.biggest_happiness();
 
         assert_eq!(330, actual.unwrap());
     }
+
+    #[test]
+    fn two_guests_test() {
+        let input = "Alice would gain 5 happiness units by sitting next to Bob.
+        Bob would lose 2 happiness units by sitting next to Alice.";
+
+        let mut table = People::parse("").unwrap();
+        table.add_self();
+        assert_eq!(0, table.biggest_happiness().unwrap());
+
+        let mut table = People::parse(input).unwrap();
+        assert_eq!(6, table.biggest_happiness().unwrap());
+
+        table.add_self();
+        assert_eq!(3, table.biggest_happiness().unwrap());
+
+        let missing = "Alice would gain 5 happiness units by sitting next to Bob.
+        Bob would lose 2 happiness units by sitting next to Alice.
+        Carol would gain 1 happiness units by sitting next to Alice.";
+        assert!(People::parse(missing).is_err());
+    }
+
+    #[test]
+    fn brute_force_agrees_test() {
+        // Everyone is keen on the guest after them, who dislikes them in return, and feels
+        // the opposite way about anyone else to how they feel about them. Only the pair's
+        // combined happiness says whether two guests should sit together.
+        let names = [
+            "Alice", "Bob", "Carol", "David", "Eric", "Frank", "George", "Mallory",
+        ];
+        let n = names.len() as i32;
+        let mut input = String::new();
+        for (a, name_a) in (0..n).zip(names) {
+            for (b, name_b) in (0..n).zip(names).filter(|&(b, _)| b != a) {
+                let happiness = if b == (a + 1) % n {
+                    30 + a * 7
+                } else if a == (b + 1) % n {
+                    -25 - b * 5
+                } else {
+                    (a - b) * 3
+                };
+                let dir = if happiness < 0 { "lose" } else { "gain" };
+                input += &format!(
+                    "{} would {} {} happiness units by sitting next to {}.\n",
+                    name_a,
+                    dir,
+                    happiness.abs(),
+                    name_b
+                );
+            }
+        }
+
+        let mut table = People::parse(input.trim()).unwrap();
+        assert_eq!(
+            table.biggest_happiness_brute_force().unwrap(),
+            table.biggest_happiness().unwrap()
+        );
+
+        table.add_self();
+        assert_eq!(
+            table.biggest_happiness_brute_force().unwrap(),
+            table.biggest_happiness().unwrap()
+        );
+    }
+
+    /// Everyone likes the guests either side of them in a circle and dislikes the rest,
+    /// so the best seating has every neighbour worth 10 units in each direction.
+    fn circle_input(guests: i32) -> String {
+        let mut input = String::new();
+        for a in 0..guests {
+            for b in (0..guests).filter(|&b| b != a) {
+                let neighbours = (a + 1) % guests == b || (b + 1) % guests == a;
+                let (dir, units) = if neighbours {
+                    ("gain", 10)
+                } else {
+                    ("lose", 3)
+                };
+                input += &format!(
+                    "G{} would {} {} happiness units by sitting next to G{}.\n",
+                    a, dir, units, b
+                );
+            }
+        }
+
+        input
+    }
+
+    #[test]
+    fn many_guests_test() {
+        let guests = 16;
+        let input = circle_input(guests);
+        let table = People::parse(input.trim()).unwrap();
+
+        assert_eq!(guests * 20, table.biggest_happiness().unwrap());
+    }
+
+    #[test]
+    fn too_many_guests_test() {
+        let input = circle_input(MAX_GUESTS as i32 + 1);
+        let table = People::parse(input.trim()).unwrap();
+        let err = table.biggest_happiness().unwrap_err();
+
+        assert_eq!(
+            "Too many guests: 21, the most supported is 20",
+            err.to_string()
+        );
+    }
 }
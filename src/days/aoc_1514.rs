@@ -1,59 +1,48 @@
-use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
+use aoc_lib::ParseResult;
 use chumsky::Parser;
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 14,
     name: "Reindeer Olympics",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u32>> {
     let reindeer: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Reindeer::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
-
-    b.bench(|| {
-        reindeer
-            .iter()
-            .map(|r| r.distance(2503))
-            .max()
-            .ok_or_else(|| eyre!("No result found"))
-    })
+        .collect::<Result<_, _>>()?;
+
+    Ok(move || part1(&reindeer, 2503))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u32>> {
     let reindeer: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Reindeer::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| Ok::<_, NoError>(part2(&reindeer, 2503).1))
+    Ok(move || Ok(part2(&reindeer, 2503).1))
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data: Vec<_> = input
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Vec<Reindeer>>> + '_> {
+    Ok(move || {
+        let data = input
             .lines()
             .map(str::trim)
             .map(Reindeer::parse)
             .collect::<Result<_, _>>()?;
-        Ok::<_, Report>(ParseResult(data))
+        Ok(ParseResult(data))
     })
 }
 
 #[derive(Debug, PartialEq)]
-struct Reindeer {
+pub struct Reindeer {
     name: String,
     flight_speed: u32,
     flight_time: u32,
@@ -61,7 +50,7 @@ struct Reindeer {
 }
 
 impl Reindeer {
-    fn parse(line: &str) -> Result<Self> {
+    pub fn parse(line: &str) -> Result<Self> {
         fn parse_line<'a>() -> impl Parser<'a, &'a str, Reindeer> {
             use chumsky::{
                 primitive::just,
@@ -98,7 +87,7 @@ impl Reindeer {
             .ok_or_else(|| eyre!("Failed to parse `{line:?}`"))
     }
 
-    fn distance(&self, total_flight_time: u32) -> u32 {
+    pub fn distance(&self, total_flight_time: u32) -> u32 {
         let cycle_time = self.flight_time + self.rest_time;
 
         let (total_cycles, partial_cycle) = (
@@ -124,7 +113,15 @@ struct RaceEntryState<'a> {
     points: u32,
 }
 
-fn part2(reindeer: &[Reindeer], total_time: u32) -> (&Reindeer, u32) {
+pub fn part1(reindeer: &[Reindeer], total_time: u32) -> Result<u32> {
+    reindeer
+        .iter()
+        .map(|r| r.distance(total_time))
+        .max()
+        .ok_or_else(|| eyre!("No result found"))
+}
+
+pub fn part2(reindeer: &[Reindeer], total_time: u32) -> (&Reindeer, u32) {
     use ReindeerFlightState::*;
 
     let mut state: Vec<_> = reindeer
@@ -1,55 +1,52 @@
 use std::ops::Neg;
 
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use aoc_lib::ParseResult;
 use chumsky::Parser;
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 15,
     name: "Science for Hungry People",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
     let ingredients: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Ingredient::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| cookie_search(&ingredients, 100, |_| true))
+    Ok(move || cookie_search(&ingredients, 100, |_| true))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<i32> + '_> {
     let ingredients: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Ingredient::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| cookie_search(&ingredients, 100, |c| c == 500))
+    Ok(move || cookie_search(&ingredients, 100, |c| c == 500))
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data: Vec<_> = input
+pub fn solve_parse<'a>(
+    input: &'a str,
+) -> Result<impl Fn() -> Result<ParseResult<Vec<Ingredient<'a>>>> + 'a> {
+    Ok(move || {
+        let data = input
             .lines()
             .map(str::trim)
             .map(Ingredient::parse)
             .collect::<Result<_, _>>()?;
-        Ok::<_, Report>(ParseResult(data))
+        Ok(ParseResult(data))
     })
 }
 
 #[derive(Debug, PartialEq)]
-struct Ingredient<'a> {
+pub struct Ingredient<'a> {
     name: &'a str,
     capacity: i32,
     durability: i32,
@@ -59,7 +56,7 @@ struct Ingredient<'a> {
 }
 
 impl<'a> Ingredient<'a> {
-    fn parse(line: &'a str) -> Result<Ingredient> {
+    pub fn parse(line: &'a str) -> Result<Ingredient<'a>> {
         fn parse_line<'a>() -> impl Parser<'a, &'a str, Ingredient<'a>> {
             use chumsky::{
                 primitive::just,
@@ -191,7 +188,7 @@ fn next_teaspoons(tsps: &mut [u32], max_teaspoons: u32) {
     }
 }
 
-fn cookie_search(
+pub fn cookie_search(
     ingredients: &[Ingredient],
     max_teaspoons: u32,
     cal_func: impl Fn(i32) -> bool,
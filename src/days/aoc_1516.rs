@@ -1,51 +1,48 @@
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
+use aoc_lib::ParseResult;
 use chumsky::{IterParser, Parser};
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 16,
     name: "Aunt Sue",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<Sue>> {
     let sues: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Sue::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| part1(&sues))
+    Ok(move || part1(&sues).cloned())
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<Sue>> {
     let sues: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Sue::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| part2(&sues))
+    Ok(move || part2(&sues).cloned())
 }
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data: Vec<_> = input
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Vec<Sue>>> + '_> {
+    Ok(move || {
+        let data = input
             .lines()
             .map(str::trim)
             .map(Sue::parse)
             .collect::<Result<_, _>>()?;
-        Ok::<_, Report>(ParseResult(data))
+        Ok(ParseResult(data))
     })
 }
 
-#[derive(Debug, Default, PartialEq)]
-struct Sue {
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Sue {
     id: u16,
     children: Option<u8>,
     cats: Option<u8>,
@@ -60,7 +57,7 @@ struct Sue {
 }
 
 impl Sue {
-    fn parse(line: &str) -> Result<Self> {
+    pub fn parse(line: &str) -> Result<Self> {
         fn parse_sue<'a>() -> impl Parser<'a, &'a str, Sue> {
             use chumsky::{
                 primitive::just,
@@ -120,7 +117,7 @@ impl std::fmt::Display for Sue {
     }
 }
 
-fn part1(sues: &[Sue]) -> Result<&Sue> {
+pub fn part1(sues: &[Sue]) -> Result<&Sue> {
     sues.iter()
         .filter(|s| !matches!(s.children, Some(v) if v != 3))
         .filter(|s| !matches!(s.cats, Some(v) if v != 7))
@@ -135,7 +132,7 @@ fn part1(sues: &[Sue]) -> Result<&Sue> {
         .ok_or_else(|| eyre!("Unable to find result"))
 }
 
-fn part2(sues: &[Sue]) -> Result<&Sue> {
+pub fn part2(sues: &[Sue]) -> Result<&Sue> {
     sues.iter()
         .filter(|s| !matches!(s.children, Some(v) if v != 3))
         .filter(|s| !matches!(s.cats, Some(v) if v <= 7))
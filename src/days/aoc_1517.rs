@@ -1,40 +1,37 @@
-use aoc_lib::{Bench, BenchResult, Day, UserError};
 use color_eyre::eyre::{eyre, Result};
 use itertools::Itertools;
 
 use std::num::ParseIntError;
 
-pub const DAY: Day = Day {
+day! {
     day: 17,
     name: "No Such Thing as Too Much",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize>> {
     let containers: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(str::parse)
-        .collect::<Result<_, ParseIntError>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, ParseIntError>>()?;
 
-    b.bench(|| part1(&containers, 150))
+    Ok(move || part1(&containers, 150))
 }
 
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize>> {
     let containers: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(str::parse)
-        .collect::<Result<_, ParseIntError>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, ParseIntError>>()?;
 
-    b.bench(|| part2(&containers, 150))
+    Ok(move || part2(&containers, 150))
 }
 
-fn part1(containers: &[u32], total_eggnog: u32) -> Result<usize> {
+pub fn part1(containers: &[u32], total_eggnog: u32) -> Result<usize> {
     let mut num_permutations = 0;
 
     for len in 1..=containers.len() {
@@ -52,7 +49,7 @@ fn part1(containers: &[u32], total_eggnog: u32) -> Result<usize> {
     }
 }
 
-fn part2(containers: &[u32], total_eggnog: u32) -> Result<usize> {
+pub fn part2(containers: &[u32], total_eggnog: u32) -> Result<usize> {
     for len in 1..=containers.len() {
         let count = containers
             .iter()
@@ -1,34 +1,30 @@
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 18,
     name: "Like a GIF For Your Yard",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
-
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let light_array = LightArray::parse(input).map_err(UserError)?;
-    b.bench(|| run_gol(light_array.clone(), false))
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let light_array = LightArray::parse(input).map_err(UserError)?;
-    b.bench(|| run_gol(light_array.clone(), true))
+
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let light_array = LightArray::parse(input)?;
+    Ok(move || run_gol(light_array.clone(), false))
+}
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let light_array = LightArray::parse(input)?;
+    Ok(move || run_gol(light_array.clone(), true))
 }
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = LightArray::parse(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<LightArray>> + '_> {
+    Ok(move || Ok(ParseResult(LightArray::parse(input)?)))
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum LightState {
+pub enum LightState {
     On,
     Off,
 }
@@ -44,14 +40,14 @@ impl LightState {
 }
 
 #[derive(Debug, Clone)]
-struct LightArray {
+pub struct LightArray {
     array: Vec<LightState>,
     buf: Vec<LightState>,
     size: usize,
 }
 
 impl LightArray {
-    fn parse(input: &str) -> Result<LightArray> {
+    pub fn parse(input: &str) -> Result<LightArray> {
         let size = input.find(['\n', '\r']).unwrap_or(input.len());
 
         let array: Vec<_> = input
@@ -141,7 +137,7 @@ impl LightArray {
     }
 }
 
-fn run_gol(mut array: LightArray, stuck: bool) -> Result<usize> {
+pub fn run_gol(mut array: LightArray, stuck: bool) -> Result<usize> {
     if stuck {
         array.apply_stuck();
     }
@@ -1,33 +1,34 @@
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Result};
 
 use std::collections::{HashMap, HashSet};
 
-pub const DAY: Day = Day {
+day! {
     day: 19,
     name: "Medicine for Rudolph",
-    part_1: run_part1,
-    part_2: None,
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let (mappings, input) = parse_input(input).map_err(UserError)?;
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    let (mappings, input) = parse_input(input)?;
+    Ok(move || part1(&mappings, input))
+}
 
-    b.bench(|| part1(&mappings, input))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize> + '_> {
+    let (mappings, input) = parse_input(input)?;
+    Ok(move || part2(&mappings, input))
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = parse_input(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+#[allow(clippy::type_complexity)]
+pub fn solve_parse<'a>(
+    input: &'a str,
+) -> Result<impl Fn() -> Result<ParseResult<(HashMap<&'a str, Vec<&'a str>>, &'a str)>> + 'a> {
+    Ok(move || Ok(ParseResult(parse_input(input)?)))
 }
 
-fn parse_input(input: &str) -> Result<(HashMap<&str, Vec<&str>>, &str)> {
+pub fn parse_input(input: &str) -> Result<(HashMap<&str, Vec<&str>>, &str)> {
     let mut mappings = HashMap::new();
 
     let mut lines = input.lines().map(str::trim);
@@ -45,7 +46,7 @@ fn parse_input(input: &str) -> Result<(HashMap<&str, Vec<&str>>, &str)> {
     Ok((mappings, input))
 }
 
-fn part1(mappings: &HashMap<&str, Vec<&str>>, input: &str) -> Result<usize> {
+pub fn part1(mappings: &HashMap<&str, Vec<&str>>, input: &str) -> Result<usize> {
     let mut seen = HashSet::new();
 
     for (from, tos) in mappings {
@@ -65,6 +66,142 @@ fn part1(mappings: &HashMap<&str, Vec<&str>>, input: &str) -> Result<usize> {
     Ok(seen.len())
 }
 
+/// Splits a molecule into its elements: an uppercase letter followed by any lowercase
+/// letters. The lone electron `e` is treated as an element too.
+fn elements(molecule: &str) -> impl Iterator<Item = &str> {
+    let mut starts: Vec<_> = molecule
+        .char_indices()
+        .filter(|(i, c)| *i == 0 || c.is_ascii_uppercase())
+        .map(|(i, _)| i)
+        .collect();
+    starts.push(molecule.len());
+
+    (0..starts.len() - 1).map(move |i| &molecule[starts[i]..starts[i + 1]])
+}
+
+pub fn part2(mappings: &HashMap<&str, Vec<&str>>, input: &str) -> Result<usize> {
+    // This is a minimum-cost CYK parse of the molecule. Every replacement is turned into
+    // binary rules by chaining through intermediate symbols that cost nothing, so only the
+    // final link of a chain counts as a step. Rules with a single element on the right are
+    // kept separate and applied as a closure over each cell.
+    let mut symbols = HashMap::new();
+    let mut intern = |s| {
+        let next = symbols.len();
+        *symbols.entry(s).or_insert(next)
+    };
+
+    let electron = intern("e");
+    let target: Vec<_> = elements(input).map(&mut intern).collect();
+    let rules: Vec<(usize, Vec<usize>)> = mappings
+        .iter()
+        .flat_map(|(&from, tos)| tos.iter().map(move |&to| (from, to)))
+        .map(|(from, to)| (intern(from), elements(to).map(&mut intern).collect()))
+        .collect();
+
+    // Intermediate symbols are numbered after every element.
+    let mut num_symbols = symbols.len();
+    let mut rules_by_left = vec![Vec::new(); num_symbols];
+    let mut unit_rules = Vec::new();
+
+    for (from, to) in rules {
+        match *to.as_slice() {
+            [] => return Err(eyre!("Empty replacement in mappings")),
+            [single] => unit_rules.push((single, from)),
+            [.., second_last, last] => {
+                // Right-fold the tail into intermediates: X -> second_last last, then
+                // Y -> third_last X, and so on until the rule's first element.
+                let mut left = second_last;
+                let mut right = last;
+                for &prev in to[..to.len() - 2].iter().rev() {
+                    let intermediate = num_symbols;
+                    num_symbols += 1;
+                    rules_by_left.push(Vec::new());
+
+                    rules_by_left[left].push((right, intermediate, 0));
+                    left = prev;
+                    right = intermediate;
+                }
+                rules_by_left[left].push((right, from, 1));
+            }
+        }
+    }
+
+    let apply_unit_rules = |cell: &mut Vec<(usize, usize)>| {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &(from, to) in &unit_rules {
+                if let Some(&(_, cost)) = cell.iter().find(|(s, _)| *s == from) {
+                    changed |= update_cell(cell, to, cost + 1);
+                }
+            }
+        }
+    };
+
+    let len = target.len();
+    if len == 0 {
+        return Err(eyre!("Empty target molecule"));
+    }
+
+    // cells[start][span - 1] holds the cheapest way to build each symbol from
+    // target[start..start + span].
+    let mut cells: Vec<Vec<Vec<(usize, usize)>>> = (0..len)
+        .map(|start| {
+            let mut spans = vec![Vec::new(); len - start];
+            spans[0].push((target[start], 0));
+            apply_unit_rules(&mut spans[0]);
+            spans
+        })
+        .collect();
+
+    for span in 2..=len {
+        for start in 0..=len - span {
+            let mut cell = Vec::new();
+
+            for split in 1..span {
+                let left_cell = &cells[start][split - 1];
+                let right_cell = &cells[start + split][span - split - 1];
+                if left_cell.is_empty() || right_cell.is_empty() {
+                    continue;
+                }
+
+                for &(left, left_cost) in left_cell {
+                    for &(right, out, cost) in &rules_by_left[left] {
+                        if let Some(&(_, right_cost)) = right_cell.iter().find(|(s, _)| *s == right)
+                        {
+                            update_cell(&mut cell, out, left_cost + right_cost + cost);
+                        }
+                    }
+                }
+            }
+
+            apply_unit_rules(&mut cell);
+            cells[start][span - 1] = cell;
+        }
+    }
+
+    cells[0][len - 1]
+        .iter()
+        .find(|(s, _)| *s == electron)
+        .map(|(_, cost)| *cost)
+        .ok_or_else(|| eyre!("Molecule cannot be produced from `e`"))
+}
+
+/// Records `cost` for `symbol` if it beats the existing entry. Returns whether the cell changed.
+fn update_cell(cell: &mut Vec<(usize, usize)>, symbol: usize, cost: usize) -> bool {
+    match cell.iter_mut().find(|(s, _)| *s == symbol) {
+        Some((_, existing)) if *existing <= cost => false,
+        Some((_, existing)) => {
+            *existing = cost;
+            true
+        }
+        None => {
+            cell.push((symbol, cost));
+            true
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests_1519 {
     use super::*;
@@ -104,4 +241,21 @@ mod tests_1519 {
             assert_eq!(part1(&mappings, test).unwrap(), expected, "{}", test);
         }
     }
+
+    #[test]
+    fn part2_example() {
+        let mappings = hashmap![
+            "e" => vec!["H", "O"],
+            "H" => vec!["HO", "OH"],
+            "O" => vec!["HH"]
+        ];
+
+        let tests = [("HOH", 3), ("HOHOHO", 6)];
+
+        for &(test, expected) in &tests {
+            assert_eq!(part2(&mappings, test).unwrap(), expected, "{}", test);
+        }
+
+        assert!(part2(&mappings, "OO").is_err());
+    }
 }
@@ -1,24 +1,24 @@
-use aoc_lib::{Bench, BenchResult, Day, UserError};
 use color_eyre::eyre::{eyre, Result};
 
-pub const DAY: Day = Day {
+day! {
     day: 20,
     name: "Infinite Elves and Infinite Houses",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let input = input.trim().parse::<usize>().map_err(UserError)?;
-    b.bench(|| part1(input))
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let input = input.trim().parse::<usize>()?;
+    Ok(move || part1(input))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let input = input.trim().parse::<usize>().map_err(UserError)?;
-    b.bench(|| part2(input))
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<usize>> {
+    let input = input.trim().parse::<usize>()?;
+    Ok(move || part2(input))
 }
 
-fn part1(num_presents: usize) -> Result<usize> {
+pub fn part1(num_presents: usize) -> Result<usize> {
     let num_presents = num_presents / 10;
     let mut houses = vec![0; num_presents + 1];
 
@@ -47,7 +47,7 @@ fn house_presents_part2(house: usize) -> usize {
     num_presents * 11
 }
 
-fn part2(num_presents: usize) -> Result<usize> {
+pub fn part2(num_presents: usize) -> Result<usize> {
     (1..)
         .find(|&h| house_presents_part2(h) >= num_presents)
         .ok_or_else(|| eyre!("Unable to find result"))
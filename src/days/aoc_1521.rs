@@ -1,56 +1,48 @@
-use aoc_lib::{Bench, BenchResult, Day, ParseResult, UserError};
-use color_eyre::{
-    eyre::{eyre, Result},
-    Report,
-};
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Result};
 use itertools::Itertools;
 
 use std::iter;
 
-pub const DAY: Day = Day {
+day! {
     day: 21,
     name: "RPG Simulator 20XX",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
-
-fn run_part1(input: &str, b: Bench) -> BenchResult {
-    let boss = Actor::parse(input).map_err(UserError)?;
-
-    let weapons = Equipment::get_weapons();
-    let armor = Equipment::get_armor();
-    let rings = Equipment::get_rings();
-
-    b.bench(|| part1(&boss, &weapons, &armor, &rings))
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
-    let boss = Actor::parse(input).map_err(UserError)?;
 
-    let weapons = Equipment::get_weapons();
-    let armor = Equipment::get_armor();
-    let rings = Equipment::get_rings();
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u16>> {
+    let boss = Actor::parse(input)?;
+    let weapons = Equipment::get_weapons()?;
+    let armor = Equipment::get_armor()?;
+    let rings = Equipment::get_rings()?;
+    Ok(move || part1(&boss, &weapons, &armor, &rings))
+}
 
-    b.bench(|| part2(&boss, &weapons, &armor, &rings))
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u16>> {
+    let boss = Actor::parse(input)?;
+    let weapons = Equipment::get_weapons()?;
+    let armor = Equipment::get_armor()?;
+    let rings = Equipment::get_rings()?;
+    Ok(move || part2(&boss, &weapons, &armor, &rings))
 }
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data = Actor::parse(input)?;
-        Ok::<_, Report>(ParseResult(data))
-    })
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Actor>> + '_> {
+    Ok(move || Ok(ParseResult(Actor::parse(input)?)))
 }
 
 #[derive(Debug)]
-struct Equipment {
-    #[allow(unused)]
-    name: &'static str,
-    cost: u16,
-    damage: i16,
-    armor: i16,
+pub struct Equipment {
+    pub name: &'static str,
+    pub cost: u16,
+    pub damage: i16,
+    pub armor: i16,
 }
 
 impl Equipment {
-    fn parse(input: &'static str) -> Equipment {
+    /// Parses a line of the shop's table, like `Dagger 8 4 0`.
+    pub fn parse(input: &'static str) -> Result<Equipment> {
         let mut parts = input.split_whitespace();
 
         let name = parts.next();
@@ -64,18 +56,18 @@ impl Equipment {
         if let (Some(name), Some(Ok(cost)), Some(Ok(damage)), Some(Ok(armor))) =
             (name, cost, damage, armor)
         {
-            Equipment {
+            Ok(Equipment {
                 name,
                 cost,
                 damage,
                 armor,
-            }
+            })
         } else {
-            panic!("Invalid equipment");
+            Err(eyre!("Invalid equipment `{}`", input))
         }
     }
 
-    fn get_weapons() -> Vec<Equipment> {
+    pub fn get_weapons() -> Result<Vec<Equipment>> {
         let vals = "Dagger        8     4       0
         Shortsword   10     5       0
         Warhammer    25     6       0
@@ -85,7 +77,7 @@ impl Equipment {
         vals.lines().map(str::trim).map(Self::parse).collect()
     }
 
-    fn get_armor() -> Vec<Equipment> {
+    pub fn get_armor() -> Result<Vec<Equipment>> {
         let vals = "Leather      13     0       1
         Chainmail    31     0       2
         Splintmail   53     0       3
@@ -95,7 +87,7 @@ impl Equipment {
         vals.lines().map(str::trim).map(Self::parse).collect()
     }
 
-    fn get_rings() -> Vec<Equipment> {
+    pub fn get_rings() -> Result<Vec<Equipment>> {
         let vals = "Damage+1    25     1       0
         Damage+2    50     2       0
         Damage+3   100     3       0
@@ -115,14 +107,14 @@ impl Equipment {
 }
 
 #[derive(Debug, Copy, Clone)]
-struct Actor {
-    hp: i16,
-    damage: i16,
-    armor: i16,
+pub struct Actor {
+    pub hp: i16,
+    pub damage: i16,
+    pub armor: i16,
 }
 
 impl Actor {
-    fn parse(input: &str) -> Result<Actor> {
+    pub fn parse(input: &str) -> Result<Actor> {
         let mut lines = input.lines().map(str::trim);
 
         let (_, hp) = lines
@@ -147,7 +139,7 @@ impl Actor {
         })
     }
 
-    fn can_defeat(&self, opponent: &Self) -> bool {
+    pub fn can_defeat(&self, opponent: &Self) -> bool {
         let self_damage = (self.damage - opponent.armor).max(1);
         let opponent_damage = (opponent.damage - self.armor).max(1);
 
@@ -165,7 +157,7 @@ impl Actor {
     }
 }
 
-fn part1(
+pub fn part1(
     boss: &Actor,
     weapons: &[Equipment],
     armor: &[Equipment],
@@ -197,7 +189,7 @@ fn part1(
     Ok(cost)
 }
 
-fn part2(
+pub fn part2(
     boss: &Actor,
     weapons: &[Equipment],
     armor: &[Equipment],
@@ -249,4 +241,14 @@ mod tests_1521 {
 
         assert!(player.can_defeat(&boss));
     }
+
+    #[test]
+    fn equipment_parse_test() {
+        let dagger = Equipment::parse("Dagger 8 4 0").unwrap();
+        assert_eq!((dagger.name, dagger.cost, dagger.damage), ("Dagger", 8, 4));
+
+        assert!(Equipment::parse("Dagger 8 four 0").is_err());
+        assert!(Equipment::parse("Dagger 8").is_err());
+        assert_eq!(Equipment::get_rings().unwrap().len(), 6);
+    }
 }
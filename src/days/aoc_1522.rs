@@ -0,0 +1,311 @@
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Result};
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+};
+
+day! {
+    day: 22,
+    name: "Wizard Simulator 20XX",
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
+
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u32>> {
+    let boss = Boss::parse(input)?;
+    Ok(move || least_mana_to_win(Wizard::PLAYER, boss, Difficulty::Normal))
+}
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u32>> {
+    let boss = Boss::parse(input)?;
+    Ok(move || least_mana_to_win(Wizard::PLAYER, boss, Difficulty::Hard))
+}
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Boss>> + '_> {
+    Ok(move || Ok(ParseResult(Boss::parse(input)?)))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    Normal,
+    Hard,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Spell {
+    MagicMissile,
+    Drain,
+    Shield,
+    Poison,
+    Recharge,
+}
+
+impl Spell {
+    const ALL: [Spell; 5] = [
+        Spell::MagicMissile,
+        Spell::Drain,
+        Spell::Shield,
+        Spell::Poison,
+        Spell::Recharge,
+    ];
+
+    fn cost(self) -> u16 {
+        match self {
+            Spell::MagicMissile => 53,
+            Spell::Drain => 73,
+            Spell::Shield => 113,
+            Spell::Poison => 173,
+            Spell::Recharge => 229,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Boss {
+    pub hp: i16,
+    pub damage: i16,
+}
+
+impl Boss {
+    pub fn parse(input: &str) -> Result<Boss> {
+        let mut lines = input.lines().map(str::trim);
+
+        let (_, hp) = lines
+            .next()
+            .and_then(|l| l.split_once(": "))
+            .ok_or_else(|| eyre!("Invalid boss input"))?;
+
+        let (_, damage) = lines
+            .next()
+            .and_then(|l| l.split_once(": "))
+            .ok_or_else(|| eyre!("Invalid boss input"))?;
+
+        Ok(Boss {
+            hp: hp.parse()?,
+            damage: damage.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Wizard {
+    pub hp: i16,
+    pub mana: u16,
+}
+
+impl Wizard {
+    pub const PLAYER: Wizard = Wizard { hp: 50, mana: 500 };
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Outcome {
+    Won,
+    Lost,
+    Ongoing(Battle),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Battle {
+    player_hp: i16,
+    player_mana: u16,
+    boss_hp: i16,
+    boss_damage: i16,
+    shield_timer: u8,
+    poison_timer: u8,
+    recharge_timer: u8,
+}
+
+impl Battle {
+    fn new(player: Wizard, boss: Boss) -> Battle {
+        Battle {
+            player_hp: player.hp,
+            player_mana: player.mana,
+            boss_hp: boss.hp,
+            boss_damage: boss.damage,
+            shield_timer: 0,
+            poison_timer: 0,
+            recharge_timer: 0,
+        }
+    }
+
+    /// Applies active effects at the start of either side's turn, returning the
+    /// player's armor for the turn.
+    fn apply_effects(&mut self) -> i16 {
+        let mut armor = 0;
+
+        if self.shield_timer > 0 {
+            armor = 7;
+            self.shield_timer -= 1;
+        }
+
+        if self.poison_timer > 0 {
+            self.boss_hp -= 3;
+            self.poison_timer -= 1;
+        }
+
+        if self.recharge_timer > 0 {
+            self.player_mana += 101;
+            self.recharge_timer -= 1;
+        }
+
+        armor
+    }
+
+    /// Plays one player turn casting `spell`, followed by the boss's turn.
+    fn play_round(mut self, spell: Spell, difficulty: Difficulty) -> Outcome {
+        // Player turn.
+        if difficulty == Difficulty::Hard {
+            self.player_hp -= 1;
+            if self.player_hp <= 0 {
+                return Outcome::Lost;
+            }
+        }
+
+        self.apply_effects();
+        if self.boss_hp <= 0 {
+            return Outcome::Won;
+        }
+
+        // You can't cast a spell whose effect is still running, and you lose if you
+        // can't afford any spell.
+        let already_active = match spell {
+            Spell::Shield => self.shield_timer > 0,
+            Spell::Poison => self.poison_timer > 0,
+            Spell::Recharge => self.recharge_timer > 0,
+            Spell::MagicMissile | Spell::Drain => false,
+        };
+        if already_active || spell.cost() > self.player_mana {
+            return Outcome::Lost;
+        }
+        self.player_mana -= spell.cost();
+
+        match spell {
+            Spell::MagicMissile => self.boss_hp -= 4,
+            Spell::Drain => {
+                self.boss_hp -= 2;
+                self.player_hp += 2;
+            }
+            Spell::Shield => self.shield_timer = 6,
+            Spell::Poison => self.poison_timer = 6,
+            Spell::Recharge => self.recharge_timer = 5,
+        }
+
+        if self.boss_hp <= 0 {
+            return Outcome::Won;
+        }
+
+        // Boss turn.
+        let armor = self.apply_effects();
+        if self.boss_hp <= 0 {
+            return Outcome::Won;
+        }
+
+        self.player_hp -= (self.boss_damage - armor).max(1);
+        if self.player_hp <= 0 {
+            return Outcome::Lost;
+        }
+
+        Outcome::Ongoing(self)
+    }
+}
+
+pub fn least_mana_to_win(player: Wizard, boss: Boss, difficulty: Difficulty) -> Result<u32> {
+    // Dijkstra over battle states, ordered by the total mana spent so far. Once the
+    // cheapest open state costs at least as much as a known win, nothing can beat it.
+    let mut queue = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    let mut best = None;
+    queue.push(Reverse((0, Battle::new(player, boss))));
+
+    while let Some(Reverse((spent, battle))) = queue.pop() {
+        if best.is_some_and(|best| spent >= best) {
+            break;
+        }
+
+        if !seen.insert(battle) {
+            continue;
+        }
+
+        for spell in Spell::ALL {
+            let cost = spent + u32::from(spell.cost());
+            match battle.play_round(spell, difficulty) {
+                Outcome::Won => best = Some(best.map_or(cost, |best: u32| best.min(cost))),
+                Outcome::Lost => {}
+                Outcome::Ongoing(next) => queue.push(Reverse((cost, next))),
+            }
+        }
+    }
+
+    best.ok_or_else(|| eyre!("Unable to find result"))
+}
+
+#[cfg(test)]
+mod tests_1522 {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let input = "Hit Points: 58
+        Damage: 9";
+
+        let expected = Boss { hp: 58, damage: 9 };
+
+        assert_eq!(expected, Boss::parse(input).unwrap());
+    }
+
+    #[test]
+    fn part1_example() {
+        let player = Wizard { hp: 10, mana: 250 };
+
+        let tests = [
+            (
+                Boss { hp: 13, damage: 8 },
+                &[Spell::Poison, Spell::MagicMissile][..],
+            ),
+            (
+                Boss { hp: 14, damage: 8 },
+                &[
+                    Spell::Recharge,
+                    Spell::Shield,
+                    Spell::Drain,
+                    Spell::Poison,
+                    Spell::MagicMissile,
+                ][..],
+            ),
+        ];
+
+        for (i, &(boss, spells)) in tests.iter().enumerate() {
+            let mut battle = Battle::new(player, boss);
+            let (last, rest) = spells.split_last().unwrap();
+
+            for &spell in rest {
+                battle = match battle.play_round(spell, Difficulty::Normal) {
+                    Outcome::Ongoing(next) => next,
+                    outcome => panic!("{}: unexpected {:?}", i, outcome),
+                };
+            }
+
+            assert_eq!(
+                battle.play_round(*last, Difficulty::Normal),
+                Outcome::Won,
+                "{}",
+                i
+            );
+        }
+
+        let boss = Boss { hp: 13, damage: 8 };
+        assert_eq!(
+            226,
+            least_mana_to_win(player, boss, Difficulty::Normal).unwrap()
+        );
+
+        let boss = Boss { hp: 14, damage: 8 };
+        assert_eq!(
+            641,
+            least_mana_to_win(player, boss, Difficulty::Normal).unwrap()
+        );
+    }
+}
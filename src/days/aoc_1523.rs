@@ -1,81 +1,82 @@
-use std::ops::{Index, IndexMut};
-
-use aoc_lib::{Bench, BenchResult, Day, NoError, ParseResult, UserError};
-use color_eyre::{
-    eyre::{eyre, Context, Result},
-    Report,
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Write},
+    ops::{Index, IndexMut},
 };
 
-pub const DAY: Day = Day {
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Context, Result};
+
+day! {
     day: 23,
     name: "Opening the Turing Lock",
-    part_1: run_part1,
-    part_2: Some(run_part2),
-    other: &[("Parse", run_parse)],
-};
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
 
-fn run_part1(input: &str, b: Bench) -> BenchResult {
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u64>> {
     let instructions: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Instruction::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| {
-        let mut computer = Computer::default();
-        computer.run_program(&instructions);
-        Ok::<_, NoError>(computer.registers.b)
-    })
+    Ok(move || run_lock(&instructions, 0))
 }
-fn run_part2(input: &str, b: Bench) -> BenchResult {
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u64>> {
     let instructions: Vec<_> = input
         .lines()
         .map(str::trim)
         .map(Instruction::parse)
-        .collect::<Result<_, _>>()
-        .map_err(UserError)?;
+        .collect::<Result<_, _>>()?;
 
-    b.bench(|| {
-        let mut computer = Computer::default();
-        computer.registers.a = 1;
-        computer.run_program(&instructions);
-        Ok::<_, NoError>(computer.registers.b)
-    })
+    Ok(move || run_lock(&instructions, 1))
 }
 
-fn run_parse(input: &str, b: Bench) -> BenchResult {
-    b.bench(|| {
-        let data: Vec<_> = input
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Vec<Instruction>>> + '_> {
+    Ok(move || {
+        let data = input
             .lines()
             .map(str::trim)
             .map(Instruction::parse)
             .collect::<Result<_, _>>()?;
-        Ok::<_, Report>(ParseResult(data))
+        Ok(ParseResult(data))
     })
 }
 
 const SEPARATORS: [char; 2] = [' ', ','];
 const OFFSET_PREFIX: [char; 2] = ['-', '+'];
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Register {
-    A,
-    B,
-}
+/// A register named by a single lowercase letter, from `a` to `z`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Register(u8);
 
 impl Register {
-    fn parse(input: &str) -> Result<Register> {
-        match input.trim() {
-            "a" => Ok(Register::A),
-            "b" => Ok(Register::B),
-            c => Err(eyre!("Unknown register: {}", c)),
+    pub const A: Register = Register(0);
+    pub const B: Register = Register(1);
+
+    pub fn parse(input: &str) -> Result<Register> {
+        match input.trim().as_bytes() {
+            &[c] if c.is_ascii_lowercase() => Ok(Register(c - b'a')),
+            _ => Err(eyre!("Unknown register: {}", input.trim())),
         }
     }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (b'a' + self.0) as char)
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Half(Register),
     Triple(Register),
     Increment(Register),
@@ -85,7 +86,7 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn parse(input: &str) -> Result<Instruction> {
+    pub fn parse(input: &str) -> Result<Instruction> {
         let mut parts = input.trim().split(SEPARATORS).filter(|s| !s.is_empty());
 
         let instr_name = parts.next();
@@ -138,67 +139,561 @@ impl Instruction {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
-struct RegisterFile {
-    a: u64,
-    b: u64,
+/// What the `Computer` should do after an instruction has executed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow {
+    Next,
+    /// Jump relative to the current instruction.
+    Jump(isize),
+    /// Emit a value, then carry on with the next instruction.
+    Output(u64),
+}
+
+/// Ways an instruction can fail, which the `Computer` reports with its location.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fault {
+    Overflow,
+}
+
+/// An instruction set the `Computer` can run. Other assembunny-style machines can add
+/// instructions such as `dec`, `cpy` or `out` by implementing this for their own type.
+pub trait InstructionSet: Copy + fmt::Display {
+    fn execute(self, registers: &mut RegisterFile) -> Result<Flow, Fault>;
+
+    /// The offset this instruction may jump by, used to label listings.
+    fn jump_offset(self) -> Option<isize> {
+        None
+    }
+
+    /// Writes the instruction with its jump offset replaced by `target`.
+    fn write_with_target(self, f: &mut dyn fmt::Write, _target: &dyn fmt::Display) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Instruction {
+    /// The instruction's mnemonic and register operand, without any jump offset.
+    fn op(self) -> (&'static str, Option<Register>) {
+        match self {
+            Instruction::Half(reg) => ("hlf", Some(reg)),
+            Instruction::Triple(reg) => ("tpl", Some(reg)),
+            Instruction::Increment(reg) => ("inc", Some(reg)),
+            Instruction::Jump(_) => ("jmp", None),
+            Instruction::JumpIfEven(reg, _) => ("jie", Some(reg)),
+            Instruction::JumpIfOne(reg, _) => ("jio", Some(reg)),
+        }
+    }
+}
+
+impl InstructionSet for Instruction {
+    fn execute(self, registers: &mut RegisterFile) -> Result<Flow, Fault> {
+        match self {
+            Instruction::Half(reg) => registers[reg] /= 2,
+            Instruction::Triple(reg) => {
+                registers[reg] = registers[reg].checked_mul(3).ok_or(Fault::Overflow)?
+            }
+            Instruction::Increment(reg) => {
+                registers[reg] = registers[reg].checked_add(1).ok_or(Fault::Overflow)?
+            }
+            Instruction::Jump(offset) => return Ok(Flow::Jump(offset)),
+            Instruction::JumpIfEven(reg, offset) if registers[reg] % 2 == 0 => {
+                return Ok(Flow::Jump(offset))
+            }
+            Instruction::JumpIfEven(_, _) => {}
+            Instruction::JumpIfOne(reg, offset) if registers[reg] == 1 => {
+                return Ok(Flow::Jump(offset))
+            }
+            Instruction::JumpIfOne(_, _) => {}
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn jump_offset(self) -> Option<isize> {
+        match self {
+            Instruction::Jump(offset)
+            | Instruction::JumpIfEven(_, offset)
+            | Instruction::JumpIfOne(_, offset) => Some(offset),
+            _ => None,
+        }
+    }
+
+    fn write_with_target(self, f: &mut dyn fmt::Write, target: &dyn fmt::Display) -> fmt::Result {
+        match self.op() {
+            (name, Some(reg)) if self.jump_offset().is_some() => {
+                write!(f, "{} {}, {}", name, reg, target)
+            }
+            (name, Some(reg)) => write!(f, "{} {}", name, reg),
+            (name, None) => write!(f, "{} {}", name, target),
+        }
+    }
+}
+
+/// Instructions are printed the way they're parsed, such as `jie a, +4`.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.jump_offset() {
+            Some(offset) => self.write_with_target(f, &format_args!("{:+}", offset)),
+            None => self.write_with_target(f, &""),
+        }
+    }
+}
+
+/// Prints a program one instruction per line, in the same syntax it's parsed from.
+pub fn print_program<I: InstructionSet>(program: &[I]) -> String {
+    let lines: Vec<_> = program.iter().map(I::to_string).collect();
+    lines.join("\n")
+}
+
+/// Prints a program with jump targets replaced by labels, like `jie a, L2`, and each
+/// target instruction marked with its label. A jump to just past the end of the program
+/// gets a label on a line of its own. Jumps further out keep their offset.
+pub fn listing<I: InstructionSet>(program: &[I]) -> String {
+    let targets: BTreeSet<usize> = program
+        .iter()
+        .enumerate()
+        .filter_map(|(pc, instr)| pc.checked_add_signed(instr.jump_offset()?))
+        .filter(|&target| target <= program.len())
+        .collect();
+
+    let label = |pc| {
+        targets
+            .iter()
+            .position(|&t| t == pc)
+            .map(|i| format!("L{}:", i))
+            .unwrap_or_default()
+    };
+
+    let mut listing = String::new();
+    for (pc, instr) in program.iter().enumerate() {
+        let target = instr
+            .jump_offset()
+            .and_then(|offset| pc.checked_add_signed(offset))
+            .filter(|target| targets.contains(target));
+
+        let mut line = format!("{:<6}", label(pc));
+        let _ = match target {
+            Some(target) => {
+                instr.write_with_target(&mut line, &label(target).trim_end_matches(':'))
+            }
+            None => write!(line, "{}", instr),
+        };
+        listing += line.trim_end();
+        listing += "\n";
+    }
+
+    let end = label(program.len());
+    if !end.is_empty() {
+        listing += &end;
+        listing += "\n";
+    }
+
+    listing
+}
+
+/// Holds however many registers the program uses. Registers that have never been
+/// written read as 0.
+#[derive(Clone, Default)]
+pub struct RegisterFile(Vec<u64>);
+
+impl RegisterFile {
+    /// Every register up to the last non-zero one, with its value.
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u64)> + '_ {
+        let len = self.0.iter().rposition(|&v| v != 0).map_or(0, |i| i + 1);
+        self.0[..len]
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (Register(i as u8), v))
+    }
+}
+
+impl fmt::Debug for RegisterFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(reg, v)| (reg.to_string(), v)))
+            .finish()
+    }
+}
+
+/// Registers compare equal if they hold the same values, however many have been written.
+impl PartialEq for RegisterFile {
+    fn eq(&self, other: &RegisterFile) -> bool {
+        self.iter().eq(other.iter())
+    }
 }
 
 impl Index<Register> for RegisterFile {
     type Output = u64;
 
     fn index(&self, index: Register) -> &Self::Output {
-        match index {
-            Register::A => &self.a,
-            Register::B => &self.b,
-        }
+        self.0.get(index.index()).unwrap_or(&0)
     }
 }
 
 impl IndexMut<Register> for RegisterFile {
     fn index_mut(&mut self, index: Register) -> &mut Self::Output {
-        match index {
-            Register::A => &mut self.a,
-            Register::B => &mut self.b,
+        if index.index() >= self.0.len() {
+            self.0.resize(index.index() + 1, 0);
         }
+        &mut self.0[index.index()]
     }
 }
 
+/// How many instructions `run_lock` executes before giving up on the program halting.
+pub const STEP_LIMIT: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VmError<I = Instruction> {
+    /// The jump at `pc` would land before the first instruction.
+    JumpBeforeStart { pc: usize, offset: isize },
+    /// The instruction at `pc` overflowed its register.
+    Overflow { pc: usize, instruction: I },
+    /// The program was still running after this many steps.
+    StepLimit(u64),
+}
+
+impl<I: fmt::Display> fmt::Display for VmError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmError::JumpBeforeStart { pc, offset } => write!(
+                f,
+                "Jump by {} at {} lands before the start of the program",
+                offset, pc
+            ),
+            VmError::Overflow { pc, instruction } => {
+                write!(f, "Register overflowed by `{}` at {}", instruction, pc)
+            }
+            VmError::StepLimit(steps) => write!(f, "Program didn't halt within {} steps", steps),
+        }
+    }
+}
+
+impl<I: fmt::Debug + fmt::Display> std::error::Error for VmError<I> {}
+
+/// Runs programs of any `InstructionSet`.
 #[derive(Debug, Clone, Default)]
-struct Computer {
-    registers: RegisterFile,
+pub struct Computer {
+    pub registers: RegisterFile,
+    pub pc: usize,
+    /// Everything emitted with `Flow::Output`, in order.
+    pub output: Vec<u64>,
 }
 
 impl Computer {
-    fn run_program(&mut self, program: &[Instruction]) {
-        let mut pc: usize = 0;
-
-        while let Some(&instr) = program.get(pc) {
-            match instr {
-                Instruction::Half(reg) => self.registers[reg] /= 2,
-                Instruction::Triple(reg) => self.registers[reg] *= 3,
-                Instruction::Increment(reg) => self.registers[reg] += 1,
-                Instruction::Jump(offset) => {
-                    pc = (pc as isize + offset) as usize;
-                    continue;
+    /// Executes the instruction at `pc`, returning it, or `None` if `pc` is outside the
+    /// program.
+    pub fn step<I: InstructionSet>(&mut self, program: &[I]) -> Result<Option<I>, VmError<I>> {
+        let instr = match program.get(self.pc) {
+            Some(&instr) => instr,
+            None => return Ok(None),
+        };
+
+        match instr.execute(&mut self.registers) {
+            Ok(Flow::Next) => self.pc += 1,
+            Ok(Flow::Jump(offset)) => {
+                self.pc = self
+                    .pc
+                    .checked_add_signed(offset)
+                    .ok_or(VmError::JumpBeforeStart {
+                        pc: self.pc,
+                        offset,
+                    })?
+            }
+            Ok(Flow::Output(value)) => {
+                self.output.push(value);
+                self.pc += 1;
+            }
+            Err(Fault::Overflow) => {
+                return Err(VmError::Overflow {
+                    pc: self.pc,
+                    instruction: instr,
+                })
+            }
+        }
+
+        Ok(Some(instr))
+    }
+
+    /// Runs the program from the start until it halts, returning how many instructions
+    /// were executed. Fails if it's still running after executing `max_steps`.
+    pub fn run_program<I: InstructionSet>(
+        &mut self,
+        program: &[I],
+        max_steps: u64,
+    ) -> Result<u64, VmError<I>> {
+        self.pc = 0;
+        for steps in 0..max_steps {
+            if self.step(program)?.is_none() {
+                return Ok(steps);
+            }
+        }
+
+        if self.pc < program.len() {
+            Err(VmError::StepLimit(max_steps))
+        } else {
+            Ok(max_steps)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Breakpoint {
+    /// Stops before executing the instruction at this index.
+    Pc(usize),
+    /// Stops once the register's value satisfies the condition.
+    Register(Register, fn(u64) -> bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stop {
+    /// The program ran off the end.
+    Halted,
+    /// The breakpoint with this index was hit.
+    Breakpoint(usize),
+}
+
+/// An executed instruction and the register values after it ran.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry<I = Instruction> {
+    pub pc: usize,
+    pub instruction: I,
+    pub registers: RegisterFile,
+}
+
+/// Runs a program on a `Computer` with breakpoints, counting how often each instruction
+/// is executed, and optionally recording every step.
+pub struct Debugger<'a, I = Instruction> {
+    pub computer: Computer,
+    program: &'a [I],
+    breakpoints: Vec<Breakpoint>,
+    hits: Vec<u64>,
+    trace: Option<Vec<TraceEntry<I>>>,
+    /// The breakpoint `run` last stopped on, and the `pc` it stopped at.
+    last_stop: Option<(usize, usize)>,
+}
+
+impl<'a, I: InstructionSet> Debugger<'a, I> {
+    pub fn new(program: &'a [I], computer: Computer) -> Debugger<'a, I> {
+        Debugger {
+            computer,
+            program,
+            breakpoints: Vec::new(),
+            hits: vec![0; program.len()],
+            trace: None,
+            last_stop: None,
+        }
+    }
+
+    /// Adds a breakpoint, returning the index `Stop::Breakpoint` will report for it.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    /// Starts recording every executed instruction.
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// The recorded steps, if tracing is enabled.
+    pub fn trace(&self) -> Option<&[TraceEntry<I>]> {
+        self.trace.as_deref()
+    }
+
+    /// How many times each instruction of the program has been executed.
+    pub fn hits(&self) -> &[u64] {
+        &self.hits
+    }
+
+    /// Executes a single instruction, ignoring breakpoints. Returns `false` if the
+    /// program has halted.
+    pub fn step(&mut self) -> Result<bool, VmError<I>> {
+        let pc = self.computer.pc;
+        let instruction = match self.computer.step(self.program)? {
+            Some(instruction) => instruction,
+            None => return Ok(false),
+        };
+
+        self.last_stop = None;
+        self.hits[pc] += 1;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                pc,
+                instruction,
+                registers: self.computer.registers.clone(),
+            });
+        }
+
+        Ok(true)
+    }
+
+    /// The first breakpoint from `first` onwards matching the computer's current state.
+    fn breakpoint_hit(&self, first: usize) -> Option<usize> {
+        (first..self.breakpoints.len()).find(|&bp| match self.breakpoints[bp] {
+            Breakpoint::Pc(pc) => self.computer.pc == pc,
+            Breakpoint::Register(reg, condition) => condition(self.computer.registers[reg]),
+        })
+    }
+
+    /// Runs until a breakpoint is hit or the program halts. Breakpoints are checked
+    /// before each instruction. Calling this again after stopping at a breakpoint checks
+    /// the breakpoints after it, then continues past it.
+    pub fn run(&mut self) -> Result<Stop, VmError<I>> {
+        let mut first = match self.last_stop.take() {
+            Some((bp, pc)) if pc == self.computer.pc => bp + 1,
+            _ => 0,
+        };
+
+        loop {
+            if self.computer.pc < self.program.len() {
+                if let Some(bp) = self.breakpoint_hit(first) {
+                    self.last_stop = Some((bp, self.computer.pc));
+                    return Ok(Stop::Breakpoint(bp));
                 }
-                Instruction::JumpIfEven(reg, offset) if self.registers[reg] % 2 == 0 => {
-                    pc = (pc as isize + offset) as usize;
-                    continue;
+            }
+            if !self.step()? {
+                return Ok(Stop::Halted);
+            }
+            first = 0;
+        }
+    }
+}
+
+/// A loop that can be run natively instead of being interpreted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Idiom {
+    /// Counts the steps it takes for `x` to reach 1 in the Collatz sequence:
+    ///
+    /// ```text
+    /// jio x, +8
+    /// inc count
+    /// jie x, +4
+    /// tpl x
+    /// inc x
+    /// jmp +2
+    /// hlf x
+    /// jmp -7
+    /// ```
+    Collatz { x: Register, count: Register },
+}
+
+impl Idiom {
+    fn detect(code: &[Instruction]) -> Option<Idiom> {
+        use Instruction::*;
+        match *code {
+            [JumpIfOne(x, 8), Increment(count), JumpIfEven(x1, 4), Triple(x2), Increment(x3), Jump(2), Half(x4), Jump(-7), ..]
+                if count != x && [x1, x2, x3, x4].iter().all(|&r| r == x) =>
+            {
+                Some(Idiom::Collatz { x, count })
+            }
+            _ => None,
+        }
+    }
+
+    fn len(self) -> usize {
+        match self {
+            Idiom::Collatz { .. } => 8,
+        }
+    }
+
+    /// Runs whole iterations of the loop starting at `computer.pc`, using at most `budget`
+    /// steps, and returns how many steps that was equivalent to. Stops at the start of an
+    /// iteration if the interpreter needs to take over, such as when a register would
+    /// overflow or the loop would never end.
+    fn run(self, computer: &mut Computer, budget: u64) -> u64 {
+        let Idiom::Collatz { x, count } = self;
+        let registers = &mut computer.registers;
+        let mut used = 0;
+
+        loop {
+            let (next, steps) = match registers[x] {
+                0 => return used,
+                1 if used < budget => {
+                    computer.pc += self.len();
+                    return used + 1;
                 }
-                Instruction::JumpIfEven(_, _) => {}
-                Instruction::JumpIfOne(reg, offset) if self.registers[reg] == 1 => {
-                    pc = (pc as isize + offset) as usize;
+                1 => return used,
+                // Even values take `jio`, `inc`, `jie`, `hlf`, `jmp`, and odd values take
+                // `jio`, `inc`, `jie`, `tpl`, `inc`, `jmp`, `jmp`.
+                v if v & 1 == 0 => (v / 2, 5),
+                v => match v.checked_mul(3).and_then(|v| v.checked_add(1)) {
+                    Some(next) => (next, 7),
+                    None => return used,
+                },
+            };
+
+            let counted = match registers[count].checked_add(1) {
+                Some(counted) if used + steps <= budget => counted,
+                _ => return used,
+            };
+
+            registers[x] = next;
+            registers[count] = counted;
+            used += steps;
+        }
+    }
+}
+
+/// A program with the loops that match an `Idiom` marked, so they can be run natively.
+pub struct Accelerated<'a> {
+    program: &'a [Instruction],
+    idioms: Vec<Option<Idiom>>,
+}
+
+impl<'a> Accelerated<'a> {
+    pub fn new(program: &'a [Instruction]) -> Accelerated<'a> {
+        Accelerated {
+            program,
+            idioms: (0..program.len())
+                .map(|pc| Idiom::detect(&program[pc..]))
+                .collect(),
+        }
+    }
+
+    /// The recognised loops, and where they start.
+    pub fn idioms(&self) -> impl Iterator<Item = (usize, Idiom)> + '_ {
+        self.idioms
+            .iter()
+            .enumerate()
+            .filter_map(|(pc, idiom)| Some((pc, (*idiom)?)))
+    }
+
+    /// Behaves exactly like `Computer::run_program`, including the step count and any
+    /// errors, but runs recognised loops natively.
+    pub fn run_program(&self, computer: &mut Computer, max_steps: u64) -> Result<u64, VmError> {
+        computer.pc = 0;
+        let mut steps = 0;
+
+        loop {
+            if computer.pc >= self.program.len() {
+                return Ok(steps);
+            }
+            if steps == max_steps {
+                return Err(VmError::StepLimit(max_steps));
+            }
+
+            if let Some(&Some(idiom)) = self.idioms.get(computer.pc) {
+                let used = idiom.run(computer, max_steps - steps);
+                if used > 0 {
+                    steps += used;
                     continue;
                 }
-                Instruction::JumpIfOne(_, _) => {}
             }
 
-            pc += 1;
+            computer.step(self.program)?;
+            steps += 1;
         }
     }
 }
 
+/// Runs the lock program with register `a` starting at `a`, returning register `b`.
+pub fn run_lock(program: &[Instruction], a: u64) -> Result<u64> {
+    let mut computer = Computer::default();
+    computer.registers[Register::A] = a;
+    Accelerated::new(program).run_program(&mut computer, STEP_LIMIT)?;
+    Ok(computer.registers[Register::B])
+}
+
 #[cfg(test)]
 mod tests_1523 {
     use super::*;
@@ -221,6 +716,34 @@ mod tests_1523 {
         }
     }
 
+    #[test]
+    fn print_test() {
+        let input = "jio a, +16
+inc a
+tpl a
+jmp +3
+jie b, -3
+hlf b";
+
+        let program: Vec<_> = input
+            .lines()
+            .map(Instruction::parse)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(input, print_program(&program));
+
+        let expected = "      jio a, +16
+L0:   inc a
+      tpl a
+      jmp L1
+      jie b, L0
+      hlf b
+L1:
+";
+        assert_eq!(expected, listing(&program));
+    }
+
     #[test]
     fn part1_example() {
         let input = "inc a
@@ -236,8 +759,248 @@ mod tests_1523 {
             .unwrap();
 
         let mut computer = Computer::default();
-        computer.run_program(&program);
+        assert_eq!(computer.run_program(&program, 100).unwrap(), 3);
+
+        assert_eq!(computer.registers[Register::A], 2);
+    }
+
+    #[test]
+    fn error_test() {
+        let run = |program: &[Instruction], a| {
+            let mut computer = Computer::default();
+            computer.registers[Register::A] = a;
+            computer.run_program(program, 100)
+        };
+
+        assert_eq!(
+            run(
+                &[Instruction::Increment(Register::A), Instruction::Jump(-2)],
+                0
+            ),
+            Err(VmError::JumpBeforeStart { pc: 1, offset: -2 })
+        );
+        assert_eq!(
+            run(&[Instruction::Jump(0)], 0),
+            Err(VmError::StepLimit(100))
+        );
+        assert_eq!(
+            run(&[Instruction::Triple(Register::A)], u64::MAX / 2),
+            Err(VmError::Overflow {
+                pc: 0,
+                instruction: Instruction::Triple(Register::A)
+            })
+        );
+        assert_eq!(run(&[Instruction::Jump(5)], 0), Ok(1));
+    }
+
+    #[test]
+    fn step_limit_test() {
+        let program = [
+            Instruction::Increment(Register::A),
+            Instruction::Increment(Register::A),
+        ];
+        let accelerated = Accelerated::new(&program);
+
+        for max_steps in 0..=3 {
+            let mut computer = Computer::default();
+            let mut accelerated_computer = Computer::default();
+            let expected = match max_steps {
+                0 | 1 => Err(VmError::StepLimit(max_steps)),
+                _ => Ok(2),
+            };
+
+            assert_eq!(expected, computer.run_program(&program, max_steps));
+            assert_eq!(
+                expected,
+                accelerated.run_program(&mut accelerated_computer, max_steps)
+            );
+            assert_eq!(max_steps.min(2), computer.registers[Register::A]);
+            assert_eq!(computer.registers, accelerated_computer.registers);
+        }
+    }
+
+    /// A cut-down assembunny instruction set, to check that other machines can run on
+    /// `Computer` without any changes to it.
+    #[derive(Debug, Clone, Copy)]
+    enum Bunny {
+        Cpy(u64, Register),
+        Dec(Register),
+        Jnz(Register, isize),
+        Out(Register),
+    }
+
+    impl fmt::Display for Bunny {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Bunny::Cpy(v, reg) => write!(f, "cpy {} {}", v, reg),
+                Bunny::Dec(reg) => write!(f, "dec {}", reg),
+                Bunny::Jnz(reg, offset) => write!(f, "jnz {} {}", reg, offset),
+                Bunny::Out(reg) => write!(f, "out {}", reg),
+            }
+        }
+    }
+
+    impl InstructionSet for Bunny {
+        fn execute(self, registers: &mut RegisterFile) -> Result<Flow, Fault> {
+            match self {
+                Bunny::Cpy(v, reg) => registers[reg] = v,
+                Bunny::Dec(reg) => {
+                    registers[reg] = registers[reg].checked_sub(1).ok_or(Fault::Overflow)?
+                }
+                Bunny::Jnz(reg, offset) if registers[reg] != 0 => return Ok(Flow::Jump(offset)),
+                Bunny::Jnz(_, _) => {}
+                Bunny::Out(reg) => return Ok(Flow::Output(registers[reg])),
+            }
+
+            Ok(Flow::Next)
+        }
+    }
+
+    #[test]
+    fn instruction_set_test() {
+        let d = Register::parse("d").unwrap();
+        let program = [
+            Bunny::Cpy(3, d),
+            Bunny::Out(d),
+            Bunny::Dec(d),
+            Bunny::Jnz(d, -2),
+        ];
+
+        let mut computer = Computer::default();
+        assert_eq!(computer.run_program(&program, 100).unwrap(), 10);
+        assert_eq!(computer.output, [3, 2, 1]);
+
+        computer.registers[d] = 0;
+        computer.pc = 2;
+        assert_eq!(
+            computer.step(&program).unwrap_err().to_string(),
+            "Register overflowed by `dec d` at 2"
+        );
+    }
+
+    #[test]
+    fn accelerated_test() {
+        let input = "jio a, +2
+        tpl a
+        jio a, +8
+        inc b
+        jie a, +4
+        tpl a
+        inc a
+        jmp +2
+        hlf a
+        jmp -7";
+
+        let program: Vec<_> = input
+            .lines()
+            .map(Instruction::parse)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        let accelerated = Accelerated::new(&program);
+        assert_eq!(
+            accelerated.idioms().collect::<Vec<_>>(),
+            [(
+                2,
+                Idiom::Collatz {
+                    x: Register::A,
+                    count: Register::B
+                }
+            )]
+        );
+
+        // Xorshift, so the test doesn't need a dependency for random numbers.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        // Small values, large values near overflow, and 0, which never halts.
+        let mut starts = vec![(0, 0), (1, 0), (27, 0), (u64::MAX / 3, 0), (7, u64::MAX)];
+        for _ in 0..200 {
+            let bits = random() % 64;
+            starts.push((random() >> bits, random() % 1000));
+        }
+
+        for (a, b) in starts {
+            for max_steps in [100, 1_000_000] {
+                let mut expected = Computer::default();
+                expected.registers[Register::A] = a;
+                expected.registers[Register::B] = b;
+                let mut actual = expected.clone();
+
+                assert_eq!(
+                    expected.run_program(&program, max_steps),
+                    accelerated.run_program(&mut actual, max_steps),
+                    "a = {}, b = {}, max_steps = {}",
+                    a,
+                    b,
+                    max_steps
+                );
+                assert_eq!(expected.registers, actual.registers, "a = {}, b = {}", a, b);
+                assert_eq!(expected.pc, actual.pc, "a = {}, b = {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn debugger_test() {
+        let program = [
+            Instruction::Increment(Register::A),
+            Instruction::JumpIfOne(Register::A, 2),
+            Instruction::Triple(Register::A),
+            Instruction::Increment(Register::A),
+            Instruction::Increment(Register::B),
+        ];
+
+        let mut debugger = Debugger::new(&program, Computer::default());
+        debugger.enable_trace();
+        let at_3 = debugger.add_breakpoint(Breakpoint::Pc(3));
+        let a_is_2 = debugger.add_breakpoint(Breakpoint::Register(Register::A, |a| a == 2));
+
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(at_3)));
+        assert_eq!(debugger.computer.pc, 3);
+        assert_eq!(debugger.computer.registers[Register::A], 1);
+
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(a_is_2)));
+        assert_eq!(debugger.computer.pc, 4);
+
+        assert_eq!(debugger.step(), Ok(true));
+        assert_eq!(debugger.step(), Ok(false));
+        assert_eq!(debugger.run(), Ok(Stop::Halted));
+
+        assert_eq!(debugger.hits(), [1, 1, 0, 1, 1]);
+
+        let trace_pcs: Vec<_> = debugger.trace().unwrap().iter().map(|t| t.pc).collect();
+        assert_eq!(trace_pcs, [0, 1, 3, 4]);
+        let registers: Vec<_> = debugger.trace().unwrap()[3].registers.iter().collect();
+        assert_eq!(registers, [(Register::A, 2), (Register::B, 1)]);
+    }
+
+    #[test]
+    fn debugger_initial_breakpoint_test() {
+        let program = [
+            Instruction::Increment(Register::A),
+            Instruction::Increment(Register::B),
+        ];
+
+        // Both breakpoints match before anything has run.
+        let mut debugger = Debugger::new(&program, Computer::default());
+        let at_0 = debugger.add_breakpoint(Breakpoint::Pc(0));
+        let b_is_0 = debugger.add_breakpoint(Breakpoint::Register(Register::B, |b| b == 0));
+
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(at_0)));
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(b_is_0)));
+        assert_eq!(debugger.computer.pc, 0);
+
+        // `b` is still 0 after the first instruction, so it stops again.
+        assert_eq!(debugger.run(), Ok(Stop::Breakpoint(b_is_0)));
+        assert_eq!(debugger.computer.pc, 1);
 
-        assert_eq!(computer.registers.a, 2);
+        assert_eq!(debugger.run(), Ok(Stop::Halted));
+        assert_eq!(debugger.hits(), [1, 1]);
     }
 }
@@ -0,0 +1,167 @@
+use aoc_lib::ParseResult;
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+
+use std::num::ParseIntError;
+
+day! {
+    day: 24,
+    name: "It Hangs in the Balance",
+    part_1: solve_part1,
+    part_2: solve_part2,
+    other: [("Parse", solve_parse)],
+}
+
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u64>> {
+    let packages = parse_input(input)?;
+    Ok(move || ideal_entanglement(&packages, 3))
+}
+
+pub fn solve_part2(input: &str) -> Result<impl Fn() -> Result<u64>> {
+    let packages = parse_input(input)?;
+    Ok(move || ideal_entanglement(&packages, 4))
+}
+
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<Vec<u64>>> + '_> {
+    Ok(move || Ok(ParseResult(parse_input(input)?)))
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<u64>, ParseIntError> {
+    input.lines().map(str::trim).map(str::parse).collect()
+}
+
+/// Checks whether `packages` can be divided into `groups` groups, each weighing `target`.
+pub fn can_split(packages: &[u64], groups: usize, target: u64) -> bool {
+    fn fill(
+        packages: &[u64],
+        used: &mut [bool],
+        start: usize,
+        remaining: u64,
+        groups: usize,
+        target: u64,
+    ) -> bool {
+        if remaining == 0 {
+            // This group is full, so the next one starts from the first unused package.
+            return split(packages, used, groups - 1, target);
+        }
+
+        for i in start..packages.len() {
+            if used[i] || packages[i] > remaining {
+                continue;
+            }
+
+            used[i] = true;
+            if fill(
+                packages,
+                used,
+                i + 1,
+                remaining - packages[i],
+                groups,
+                target,
+            ) {
+                return true;
+            }
+            used[i] = false;
+        }
+
+        false
+    }
+
+    fn split(packages: &[u64], used: &mut [bool], groups: usize, target: u64) -> bool {
+        // The last group is whatever is left over, which has to weigh `target` already.
+        if groups <= 1 {
+            return true;
+        }
+
+        match used.iter().position(|u| !u) {
+            Some(first) => {
+                // Fixing the first free package in the current group avoids trying
+                // every ordering of the same set of groups.
+                used[first] = true;
+                let found = packages[first] <= target
+                    && fill(
+                        packages,
+                        used,
+                        first + 1,
+                        target - packages[first],
+                        groups,
+                        target,
+                    );
+                used[first] = false;
+                found
+            }
+            None => false,
+        }
+    }
+
+    if packages.iter().sum::<u64>() != target * groups as u64 {
+        return false;
+    }
+
+    let mut used = vec![false; packages.len()];
+    split(packages, &mut used, groups, target)
+}
+
+pub fn ideal_entanglement(packages: &[u64], groups: usize) -> Result<u64> {
+    if groups == 0 {
+        return Err(eyre!("Need at least one group"));
+    }
+
+    let total: u64 = packages.iter().sum();
+    let target = total / groups as u64;
+    if target * groups as u64 != total {
+        return Err(eyre!(
+            "Packages cannot be split into {} equal groups",
+            groups
+        ));
+    }
+
+    for len in 1..=packages.len() {
+        let mut candidates: Vec<(u64, Vec<usize>)> = (0..packages.len())
+            .combinations(len)
+            .filter(|group| group.iter().map(|&i| packages[i]).sum::<u64>() == target)
+            .map(|group| (group.iter().map(|&i| packages[i]).product(), group))
+            .collect();
+
+        candidates.sort_unstable();
+
+        for (entanglement, group) in candidates {
+            let rest: Vec<_> = (0..packages.len())
+                .filter(|i| !group.contains(i))
+                .map(|i| packages[i])
+                .collect();
+
+            if can_split(&rest, groups - 1, target) {
+                return Ok(entanglement);
+            }
+        }
+    }
+
+    Err(eyre!("Unable to find result"))
+}
+
+#[cfg(test)]
+mod tests_1524 {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let packages = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+        assert_eq!(99, ideal_entanglement(&packages, 3).unwrap());
+    }
+
+    #[test]
+    fn part2_example() {
+        let packages = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+
+        assert_eq!(44, ideal_entanglement(&packages, 4).unwrap());
+    }
+
+    #[test]
+    fn split_test() {
+        assert!(can_split(&[1, 2, 3, 4, 5], 3, 5));
+        assert!(!can_split(&[1, 3, 3, 3], 2, 5));
+        assert!(!can_split(&[3, 3, 4], 2, 5));
+    }
+}
@@ -1,26 +1,110 @@
-#![allow(clippy::unnecessary_wraps)]
-
-use aoc_lib::TracingAlloc;
+use aoc_lib::ParseResult;
 use color_eyre::eyre::{eyre, Result};
 
-#[global_allocator]
-static ALLOC: TracingAlloc = TracingAlloc::new();
+day! {
+    day: 25,
+    name: "Let It Snow",
+    part_1: solve_part1,
+    other: [("Parse", solve_parse)],
+}
+
+pub fn solve_part1(input: &str) -> Result<impl Fn() -> Result<u64>> {
+    let position = GridPosition::parse(input)?;
+    Ok(move || Ok(position.code()))
+}
 
-fn main() -> Result<()> {
-    color_eyre::install()?;
+pub fn solve_parse(input: &str) -> Result<impl Fn() -> Result<ParseResult<GridPosition>> + '_> {
+    Ok(move || Ok(ParseResult(GridPosition::parse(input)?)))
+}
 
-    let input = std::fs::read_to_string("inputs/aoc_1525.txt")?;
+const FIRST_CODE: u64 = 20151125;
+const MULTIPLIER: u64 = 252533;
+const MODULUS: u64 = 33554393;
 
-    aoc_lib::run(
-        &ALLOC,
-        "Day 25: Let It Snow",
-        &input,
-        &|_| Ok("Not Implemented"),
-        &|_| Ok("Not Implemented"),
-    )
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridPosition {
+    row: u64,
+    column: u64,
+}
+
+impl GridPosition {
+    pub fn parse(input: &str) -> Result<GridPosition> {
+        let (_, rest) = input
+            .split_once("row ")
+            .ok_or_else(|| eyre!("Unable to find row in `{input:?}`"))?;
+        let (row, rest) = rest
+            .split_once(", column ")
+            .ok_or_else(|| eyre!("Unable to find column in `{input:?}`"))?;
+        let column = rest.trim().trim_end_matches('.');
+
+        let position = GridPosition {
+            row: row.trim().parse()?,
+            column: column.parse()?,
+        };
+
+        if position.row == 0 || position.column == 0 {
+            return Err(eyre!("Row and column start at 1"));
+        }
+
+        Ok(position)
+    }
+
+    /// How many codes come before this one, filling the grid one diagonal at a time.
+    fn index(self) -> u64 {
+        let diagonal = self.row + self.column - 1;
+        diagonal * (diagonal - 1) / 2 + self.column - 1
+    }
+
+    pub fn code(self) -> u64 {
+        FIRST_CODE * mod_pow(MULTIPLIER, self.index(), MODULUS) % MODULUS
+    }
+}
+
+fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    base %= modulus;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+
+    result
 }
 
 #[cfg(test)]
 mod tests_1525 {
     use super::*;
+
+    #[test]
+    fn parse_test() {
+        let input = "To continue, please consult the code grid in the manual.  Enter the code at row 2981, column 3075.";
+
+        let expected = GridPosition {
+            row: 2981,
+            column: 3075,
+        };
+
+        assert_eq!(expected, GridPosition::parse(input).unwrap());
+    }
+
+    #[test]
+    fn part1_example() {
+        let tests = [
+            ((1, 1), 20151125),
+            ((2, 1), 31916031),
+            ((1, 2), 18749137),
+            ((3, 4), 7981243),
+            ((4, 3), 21345942),
+            ((6, 6), 27995004),
+        ];
+
+        for &((row, column), expected) in &tests {
+            let position = GridPosition { row, column };
+            assert_eq!(position.code(), expected, "{:?}", position);
+        }
+    }
 }
@@ -0,0 +1,353 @@
+use color_eyre::{
+    eyre::{eyre, Result, WrapErr},
+    Report,
+};
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    hint::black_box,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+pub mod alloc;
+pub mod baseline;
+pub mod bench;
+pub mod export;
+
+use alloc::TrackingAlloc;
+use bench::{Entry, Stats};
+
+/// Solves one part of a day, handing the measured section to the `Run`.
+///
+/// These are built by `day!` from the same entry functions as each day's `DAY`: input
+/// preparation happens outside of `Run::run`, and the closure given to it is what
+/// computes the answer.
+pub type SolveFn = fn(&str, &mut Run) -> Result<String>;
+
+/// Picks out the part of a printed answer that's checked against `answers.txt`.
+pub type AnswerFn = fn(&str) -> &str;
+
+/// The `AnswerFn` for days whose printed answer is just the answer.
+pub fn whole_answer(answer: &str) -> &str {
+    answer
+}
+
+pub struct Solution {
+    pub day: u8,
+    pub part_1: SolveFn,
+    pub part_2: Option<SolveFn>,
+    pub answer: AnswerFn,
+    pub other: &'static [(&'static str, SolveFn)],
+}
+
+impl Solution {
+    /// Iterates over the day's parts as `(part number, solver)`.
+    pub fn parts(&self) -> impl Iterator<Item = (u8, SolveFn)> {
+        std::iter::once((1, self.part_1)).chain(self.part_2.map(|p| (2, p)))
+    }
+
+    /// Whether `actual` is the expected answer, once the day's `answer` function has
+    /// picked out the part to check.
+    pub fn answer_matches(&self, actual: &str, expected: &str) -> bool {
+        (self.answer)(actual) == expected
+    }
+}
+
+/// Drives the measured section of a solver.
+///
+/// By default the section runs once and only the answer is kept. A benchmarking run also
+/// measures allocations on one pass, then times repeated passes.
+#[derive(Debug, Default)]
+pub struct Run {
+    bench: Option<BenchConfig>,
+    stats: Option<Stats>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub alloc: &'static TrackingAlloc,
+    /// How long to keep sampling for, once the minimum number of samples is taken.
+    pub budget: Duration,
+}
+
+impl Run {
+    pub fn bench(config: BenchConfig) -> Run {
+        Run {
+            bench: Some(config),
+            stats: None,
+        }
+    }
+
+    /// The measurements from the last call to `run`, if benchmarking.
+    pub fn stats(&self) -> Option<Stats> {
+        self.stats
+    }
+
+    pub fn run<T, E>(&mut self, f: impl Fn() -> Result<T, E>) -> Result<String>
+    where
+        T: Display,
+        E: Into<Report>,
+    {
+        let config = match self.bench {
+            Some(config) => config,
+            None => return f().map(|answer| answer.to_string()).map_err(Into::into),
+        };
+
+        config.alloc.reset();
+        let answer = f().map_err(Into::into)?;
+        let alloc_stats = config.alloc.stats();
+        let answer = answer.to_string();
+
+        let timings = bench::sample(config.budget, || {
+            // Errors were already handled on the first pass.
+            let _ = black_box(f());
+        });
+        self.stats = Some(Stats::new(&timings, alloc_stats));
+
+        Ok(answer)
+    }
+}
+
+pub fn input_path(inputs: &Path, day: u8) -> PathBuf {
+    inputs.join(format!("aoc_15{:02}.txt", day))
+}
+
+/// Reads a puzzle input from `path`, or from stdin if `path` is `-`. Surrounding
+/// whitespace is trimmed, as the solvers don't expect a trailing newline.
+pub fn load_input(path: &Path) -> Result<String> {
+    let input = if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .wrap_err("Unable to read input from stdin")?;
+        input
+    } else {
+        fs::read_to_string(path)
+            .with_context(|| eyre!("Unable to read input from {}", path.display()))?
+    };
+
+    Ok(input.trim().to_owned())
+}
+
+/// Finds the entries of `day` picked by `selector`: a part number, or the name of one of
+/// the day's `other` entries. Without a selector, every entry is picked.
+pub fn select(
+    solutions: &[Solution],
+    day: u8,
+    selector: Option<&str>,
+) -> Result<Vec<(Entry, SolveFn)>> {
+    let solution = solutions
+        .iter()
+        .find(|s| s.day == day)
+        .ok_or_else(|| eyre!("No solution for day {}", day))?;
+
+    let entries: Vec<_> = solution
+        .entries()
+        .filter(|(entry, _)| match (selector, entry) {
+            (None, _) => true,
+            (Some(selector), Entry::Part(part)) => selector == part.to_string(),
+            (Some(selector), Entry::Other(name)) => selector.eq_ignore_ascii_case(name),
+        })
+        .collect();
+
+    if entries.is_empty() {
+        let available: Vec<_> = solution.entries().map(|(e, _)| e.to_string()).collect();
+        return Err(eyre!(
+            "Day {} has no entry `{}`, expected one of: {}",
+            day,
+            selector.unwrap_or_default(),
+            available.join(", ")
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// Expected answers, keyed by `(day, part)`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(HashMap<(u8, u8), String>);
+
+impl Answers {
+    /// Parses lines of the form `7.1 46065`, using the same `day.part` numbering as the
+    /// results table. Everything after the key is the answer, so answers may contain
+    /// spaces. Blank lines and lines starting with `#` are ignored.
+    pub fn parse(input: &str) -> Result<Answers> {
+        let mut answers = HashMap::new();
+
+        for (line_no, line) in input.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parse_line = || -> Option<((u8, u8), &str)> {
+                let (key, answer) = line.split_once(char::is_whitespace)?;
+                let (day, part) = key.split_once('.')?;
+                Some(((day.parse().ok()?, part.parse().ok()?), answer.trim()))
+            };
+
+            let (key, answer) = parse_line()
+                .ok_or_else(|| eyre!("Invalid answer on line {}: `{line:?}`", line_no + 1))?;
+
+            if answers.insert(key, answer.to_owned()).is_some() {
+                return Err(eyre!(
+                    "Duplicate answer for {}.{} on line {}",
+                    key.0,
+                    key.1,
+                    line_no + 1
+                ));
+            }
+        }
+
+        Ok(Answers(answers))
+    }
+
+    pub fn load(path: &Path) -> Result<Answers> {
+        let file = fs::read_to_string(path)
+            .with_context(|| eyre!("Unable to read answers from {}", path.display()))?;
+        Answers::parse(&file)
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String, actual: String },
+    Failed(String),
+}
+
+/// Runs every part that has an expected answer once, without any benchmarking, and
+/// prints a line per part. Parts with no expected answer are listed as missing. Returns
+/// `false` if anything didn't match, or if any answers were missing and `allow_missing`
+/// isn't set.
+pub fn verify(
+    solutions: &[Solution],
+    answers: &Answers,
+    inputs: &Path,
+    allow_missing: bool,
+) -> Result<bool> {
+    let mut all_correct = true;
+
+    for solution in solutions {
+        let mut input = None;
+
+        for (part, f) in solution.parts() {
+            let day_part = format!("{}.{}", solution.day, part);
+            let expected = match answers.get(solution.day, part) {
+                Some(expected) => expected,
+                None => {
+                    println!("{:>6} | MISSING  | no expected answer", day_part);
+                    all_correct &= allow_missing;
+                    continue;
+                }
+            };
+
+            // Only read the input once a part needs it.
+            let input = match &input {
+                Some(input) => input,
+                None => input.insert(load_input(&input_path(inputs, solution.day))?),
+            };
+
+            let verdict = match f(input, &mut Run::default()) {
+                Ok(actual) if solution.answer_matches(&actual, expected) => Verdict::Correct,
+                Ok(actual) => Verdict::Wrong {
+                    expected: expected.to_owned(),
+                    actual,
+                },
+                Err(e) => Verdict::Failed(format!("{:#}", e)),
+            };
+
+            match &verdict {
+                Verdict::Correct => println!("{:>6} | ok       | {}", day_part, expected),
+                Verdict::Wrong { expected, actual } => println!(
+                    "{:>6} | MISMATCH | expected {}, got {}",
+                    day_part, expected, actual
+                ),
+                Verdict::Failed(e) => println!("{:>6} | ERROR    | {}", day_part, e),
+            }
+
+            all_correct &= verdict == Verdict::Correct;
+        }
+    }
+
+    Ok(all_correct)
+}
+
+#[cfg(test)]
+mod tests_harness {
+    use super::*;
+
+    #[test]
+    fn answers_parse_test() {
+        let input = "# Comment
+        1.1 138
+
+        16.2 Sue 260";
+
+        let answers = Answers::parse(input).unwrap();
+
+        assert_eq!(answers.get(1, 1), Some("138"));
+        assert_eq!(answers.get(16, 2), Some("Sue 260"));
+        assert_eq!(answers.get(1, 2), None);
+
+        assert!(Answers::parse("1.1 138\n1.1 139").is_err());
+        assert!(Answers::parse("1 138").is_err());
+    }
+
+    #[test]
+    fn answer_matches_test() {
+        use crate::days::{aoc_1501, aoc_1509, aoc_1516};
+
+        assert!(aoc_1501::SOLUTION.answer_matches("605", "605"));
+        assert!(aoc_1509::SOLUTION.answer_matches("London -> Dublin -> Belfast (605)", "605"));
+        assert!(!aoc_1509::SOLUTION.answer_matches("London -> Dublin -> Belfast (605)", "982"));
+        assert!(!aoc_1509::SOLUTION.answer_matches("6050", "605"));
+        assert!(!aoc_1516::SOLUTION.answer_matches("Sue (260)", "260"));
+    }
+
+    fn test_solve(_: &str, r: &mut Run) -> Result<String> {
+        r.run(|| Ok::<_, Report>(0))
+    }
+
+    const TEST_SOLUTIONS: &[Solution] = &[Solution {
+        day: 7,
+        part_1: test_solve,
+        part_2: Some(test_solve),
+        answer: whole_answer,
+        other: &[("Parse", test_solve)],
+    }];
+
+    #[test]
+    fn verify_missing_test() {
+        let answers = Answers::default();
+        let inputs = Path::new("no_such_dir");
+
+        assert!(!verify(TEST_SOLUTIONS, &answers, inputs, false).unwrap());
+        assert!(verify(TEST_SOLUTIONS, &answers, inputs, true).unwrap());
+    }
+
+    #[test]
+    fn select_test() {
+        let select = |day, selector| -> Result<Vec<Entry>> {
+            let entries = super::select(TEST_SOLUTIONS, day, selector)?;
+            Ok(entries.into_iter().map(|(e, _)| e).collect())
+        };
+
+        assert_eq!(
+            select(7, None).unwrap(),
+            [Entry::Part(1), Entry::Part(2), Entry::Other("Parse")]
+        );
+        assert_eq!(select(7, Some("2")).unwrap(), [Entry::Part(2)]);
+        assert_eq!(select(7, Some("parse")).unwrap(), [Entry::Other("Parse")]);
+
+        assert!(select(7, Some("3")).is_err());
+        assert!(select(8, None).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Counts allocations and tracks peak memory use on top of the system allocator.
+///
+/// Install this as the `#[global_allocator]` of any binary that benchmarks through the
+/// harness, then hand it to `Run::bench`.
+///
+/// The counters are kept per thread, so days benchmarked on different threads don't see
+/// each other's allocations. `reset` and `stats` only cover the calling thread.
+#[derive(Debug, Default)]
+pub struct TrackingAlloc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AllocStats {
+    pub allocs: usize,
+    pub max_memory: usize,
+}
+
+/// Memory is signed because a thread can free memory that another thread allocated.
+struct Counters {
+    allocs: Cell<usize>,
+    current: Cell<isize>,
+    baseline: Cell<isize>,
+    peak: Cell<isize>,
+}
+
+thread_local! {
+    // Const-initialized and without a destructor, so accessing it never allocates and
+    // stays valid for the whole life of the thread.
+    static COUNTERS: Counters = const {
+        Counters {
+            allocs: Cell::new(0),
+            current: Cell::new(0),
+            baseline: Cell::new(0),
+            peak: Cell::new(0),
+        }
+    };
+}
+
+impl TrackingAlloc {
+    pub const fn new() -> TrackingAlloc {
+        TrackingAlloc
+    }
+
+    /// Starts a new measurement from the calling thread's current memory use.
+    pub fn reset(&self) {
+        COUNTERS.with(|c| {
+            c.allocs.set(0);
+            c.baseline.set(c.current.get());
+            c.peak.set(c.current.get());
+        });
+    }
+
+    /// The allocations made by the calling thread since the last `reset`, and the most
+    /// memory it had in use above what it had at the time.
+    pub fn stats(&self) -> AllocStats {
+        COUNTERS.with(|c| AllocStats {
+            allocs: c.allocs.get(),
+            max_memory: (c.peak.get() - c.baseline.get()).max(0) as usize,
+        })
+    }
+
+    fn record_alloc(&self, size: usize) {
+        let _ = COUNTERS.try_with(|c| {
+            c.allocs.set(c.allocs.get() + 1);
+            let current = c.current.get() + size as isize;
+            c.current.set(current);
+            c.peak.set(c.peak.get().max(current));
+        });
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        let _ = COUNTERS.try_with(|c| c.current.set(c.current.get() - size as isize));
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.record_dealloc(layout.size());
+            self.record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests_alloc {
+    use super::*;
+
+    #[test]
+    fn per_thread_test() {
+        static ALLOC: TrackingAlloc = TrackingAlloc::new();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        ALLOC.reset();
+        let ptr = unsafe { ALLOC.alloc(layout) };
+
+        // Another thread's allocations don't show up in this thread's stats.
+        std::thread::spawn(move || {
+            ALLOC.reset();
+            let other = unsafe { ALLOC.alloc(Layout::from_size_align(1024, 8).unwrap()) };
+            assert_eq!(
+                ALLOC.stats(),
+                AllocStats {
+                    allocs: 1,
+                    max_memory: 1024
+                }
+            );
+            unsafe { ALLOC.dealloc(other, Layout::from_size_align(1024, 8).unwrap()) };
+        })
+        .join()
+        .unwrap();
+
+        unsafe { ALLOC.dealloc(ptr, layout) };
+        assert_eq!(
+            ALLOC.stats(),
+            AllocStats {
+                allocs: 1,
+                max_memory: 64
+            }
+        );
+    }
+}
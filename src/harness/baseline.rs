@@ -0,0 +1,222 @@
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde_json::Value;
+
+use super::bench::{Record, Stats};
+
+use std::{collections::HashMap, fmt, fs, path::Path, time::Duration};
+
+/// Welch's t statistic a slowdown has to exceed before it's reported. Sub-microsecond
+/// entries are noisy, so this is deliberately stricter than the usual 95% level.
+const T_CRITICAL: f64 = 3.0;
+
+/// A slowdown also has to be at least this large, relative to the baseline mean, so that
+/// very tight distributions don't flag trivial changes.
+const MIN_SLOWDOWN: f64 = 0.05;
+
+/// Results from an earlier run, as written by `export::to_json`, keyed by day and entry.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline(HashMap<(u8, String), Stats>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Regression {
+    Slower {
+        baseline: Duration,
+        current: Duration,
+        t: f64,
+    },
+    MoreAllocs {
+        baseline: usize,
+        current: usize,
+    },
+    MoreMemory {
+        baseline: usize,
+        current: usize,
+    },
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Regression::Slower {
+                baseline,
+                current,
+                t,
+            } => write!(
+                f,
+                "time {:.3?} -> {:.3?} (+{:.1}%, t = {:.1})",
+                baseline,
+                current,
+                (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0,
+                t
+            ),
+            Regression::MoreAllocs { baseline, current } => {
+                write!(f, "allocs {} -> {}", baseline, current)
+            }
+            Regression::MoreMemory { baseline, current } => {
+                write!(f, "max memory {} B -> {} B", baseline, current)
+            }
+        }
+    }
+}
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Baseline> {
+        let records: Vec<Value> = serde_json::from_str(input)?;
+        let mut baseline = HashMap::new();
+
+        for record in &records {
+            let field = |name: &str| {
+                record[name]
+                    .as_u64()
+                    .ok_or_else(|| eyre!("Baseline record is missing `{}`: {}", name, record))
+            };
+
+            let day = field("day")? as u8;
+            let entry = record["entry"]
+                .as_str()
+                .ok_or_else(|| eyre!("Baseline record is missing `entry`: {}", record))?;
+
+            let stats = Stats {
+                mean: Duration::from_nanos(field("mean_ns")?),
+                std_dev: Duration::from_nanos(field("std_dev_ns")?),
+                samples: field("samples")? as usize,
+                allocs: field("allocs")? as usize,
+                max_memory: field("max_memory_bytes")? as usize,
+            };
+
+            baseline.insert((day, entry.to_owned()), stats);
+        }
+
+        Ok(Baseline(baseline))
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline> {
+        let file = fs::read_to_string(path)
+            .with_context(|| eyre!("Unable to read baseline from {}", path.display()))?;
+        Baseline::parse(&file).with_context(|| eyre!("Invalid baseline in {}", path.display()))
+    }
+
+    pub fn get(&self, record: &Record) -> Option<&Stats> {
+        self.0.get(&(record.day, record.entry.to_string()))
+    }
+
+    /// Compares each record against its baseline entry. Entries missing from the
+    /// baseline are skipped.
+    pub fn compare<'a>(&self, records: &'a [Record]) -> Vec<(&'a Record, Vec<Regression>)> {
+        records
+            .iter()
+            .filter_map(|record| {
+                let baseline = self.get(record)?;
+                let regressions = regressions(baseline, &record.stats);
+                (!regressions.is_empty()).then_some((record, regressions))
+            })
+            .collect()
+    }
+}
+
+/// Welch's t statistic for `current` being slower than `baseline`.
+fn welch_t(baseline: &Stats, current: &Stats) -> f64 {
+    let variance = |s: &Stats| s.std_dev.as_secs_f64().powi(2) / s.samples.max(1) as f64;
+    let diff = current.mean.as_secs_f64() - baseline.mean.as_secs_f64();
+    let std_err = (variance(baseline) + variance(current)).sqrt();
+
+    if std_err == 0.0 {
+        // Both runs were perfectly consistent, so any difference is real.
+        match diff {
+            d if d > 0.0 => f64::INFINITY,
+            d if d < 0.0 => f64::NEG_INFINITY,
+            _ => 0.0,
+        }
+    } else {
+        diff / std_err
+    }
+}
+
+pub fn regressions(baseline: &Stats, current: &Stats) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+
+    let t = welch_t(baseline, current);
+    let slowdown = current.mean.as_secs_f64() / baseline.mean.as_secs_f64().max(f64::EPSILON);
+    if t > T_CRITICAL && slowdown > 1.0 + MIN_SLOWDOWN {
+        regressions.push(Regression::Slower {
+            baseline: baseline.mean,
+            current: current.mean,
+            t,
+        });
+    }
+
+    // Allocations are deterministic, so any increase counts.
+    if current.allocs > baseline.allocs {
+        regressions.push(Regression::MoreAllocs {
+            baseline: baseline.allocs,
+            current: current.allocs,
+        });
+    }
+
+    if current.max_memory > baseline.max_memory {
+        regressions.push(Regression::MoreMemory {
+            baseline: baseline.max_memory,
+            current: current.max_memory,
+        });
+    }
+
+    regressions
+}
+
+#[cfg(test)]
+mod tests_baseline {
+    use super::*;
+    use crate::harness::{bench::Entry, export};
+
+    fn stats(mean_us: u64, std_dev_us: u64, allocs: usize, max_memory: usize) -> Stats {
+        Stats {
+            mean: Duration::from_micros(mean_us),
+            std_dev: Duration::from_micros(std_dev_us),
+            samples: 100,
+            allocs,
+            max_memory,
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let records = vec![Record {
+            day: 10,
+            entry: Entry::Part(2),
+            answer: "4666278".to_owned(),
+            stats: stats(125_900, 8_286, 40, 12_700_000),
+        }];
+
+        let baseline = Baseline::parse(&export::to_json(&records)).unwrap();
+
+        assert_eq!(baseline.get(&records[0]), Some(&records[0].stats));
+    }
+
+    #[test]
+    fn regression_test() {
+        let baseline = stats(1000, 50, 2, 100);
+
+        // Within noise.
+        assert!(regressions(&baseline, &stats(1010, 50, 2, 100)).is_empty());
+        // Faster isn't a regression.
+        assert!(regressions(&baseline, &stats(500, 50, 2, 100)).is_empty());
+
+        let slower = regressions(&baseline, &stats(1200, 50, 2, 100));
+        assert!(matches!(slower[..], [Regression::Slower { .. }]));
+
+        let heavier = regressions(&baseline, &stats(1000, 50, 3, 200));
+        assert_eq!(
+            heavier,
+            vec![
+                Regression::MoreAllocs {
+                    baseline: 2,
+                    current: 3
+                },
+                Regression::MoreMemory {
+                    baseline: 100,
+                    current: 200
+                },
+            ]
+        );
+    }
+}
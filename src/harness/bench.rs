@@ -0,0 +1,202 @@
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use super::{alloc::AllocStats, input_path, load_input, BenchConfig, Run, Solution, SolveFn};
+
+use std::{
+    fmt,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+const MIN_SAMPLES: usize = 10;
+const MAX_SAMPLES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Stats {
+    pub mean: Duration,
+    pub std_dev: Duration,
+    pub samples: usize,
+    pub allocs: usize,
+    pub max_memory: usize,
+}
+
+impl Stats {
+    pub fn new(timings: &[Duration], alloc: AllocStats) -> Stats {
+        let samples = timings.len();
+        let (mean, std_dev) = if samples == 0 {
+            (0.0, 0.0)
+        } else {
+            let secs = timings.iter().map(Duration::as_secs_f64);
+            let mean = secs.clone().sum::<f64>() / samples as f64;
+            let variance = secs.map(|t| (t - mean).powi(2)).sum::<f64>() / samples as f64;
+            (mean, variance.sqrt())
+        };
+
+        Stats {
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(std_dev),
+            samples,
+            allocs: alloc.allocs,
+            max_memory: alloc.max_memory,
+        }
+    }
+}
+
+/// Times `f` at least `MIN_SAMPLES` times, then keeps going until `budget` runs out.
+pub fn sample(budget: Duration, mut f: impl FnMut()) -> Vec<Duration> {
+    let mut timings = Vec::with_capacity(MIN_SAMPLES);
+    let start = Instant::now();
+
+    while timings.len() < MIN_SAMPLES || (start.elapsed() < budget && timings.len() < MAX_SAMPLES) {
+        let sample_start = Instant::now();
+        f();
+        timings.push(sample_start.elapsed());
+    }
+
+    timings
+}
+
+/// A part, or one of a day's `other` entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Entry {
+    Part(u8),
+    Other(&'static str),
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Part(part) => write!(f, "{}", part),
+            Entry::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: u8,
+    pub entry: Entry,
+    pub answer: String,
+    pub stats: Stats,
+}
+
+impl Entry {
+    /// The row label used in the results table, e.g. `7.1` or `7 Parse`.
+    pub fn label(self, day: u8) -> String {
+        match self {
+            Entry::Part(part) => format!("{}.{}", day, part),
+            Entry::Other(name) => format!("{} {}", day, name),
+        }
+    }
+}
+
+impl Record {
+    pub fn label(&self) -> String {
+        self.entry.label(self.day)
+    }
+}
+
+impl Solution {
+    /// Every part followed by the `other` entries, in table order.
+    pub fn entries(&self) -> impl Iterator<Item = (Entry, SolveFn)> + '_ {
+        self.parts()
+            .map(|(part, f)| (Entry::Part(part), f))
+            .chain(self.other.iter().map(|&(name, f)| (Entry::Other(name), f)))
+    }
+}
+
+pub fn bench_entry(
+    day: u8,
+    entry: Entry,
+    f: SolveFn,
+    input: &str,
+    config: BenchConfig,
+) -> Result<Record> {
+    let mut run = Run::bench(config);
+    let answer = f(input, &mut run).with_context(|| eyre!("Day {}", entry.label(day)))?;
+    let stats = run
+        .stats()
+        .ok_or_else(|| eyre!("Day {} never ran its measured section", entry.label(day)))?;
+
+    Ok(Record {
+        day,
+        entry,
+        answer,
+        stats,
+    })
+}
+
+/// Benchmarks every entry of one day. Failing to read the input fails the whole day.
+fn bench_day(solution: &Solution, inputs: &Path, config: BenchConfig) -> Vec<Result<Record>> {
+    let input = match load_input(&input_path(inputs, solution.day)) {
+        Ok(input) => input,
+        Err(e) => return vec![Err(e)],
+    };
+
+    solution
+        .entries()
+        .map(|(entry, f)| bench_entry(solution.day, entry, f, &input, config))
+        .collect()
+}
+
+/// Benchmarks every entry of every day, spreading the days over `jobs` worker threads.
+/// The records are returned in day order regardless of which finished first, and a
+/// failing entry doesn't stop the rest.
+///
+/// Allocations are tracked per thread, so running days in parallel doesn't affect their
+/// allocation stats, but they do compete for the CPU, which affects their timings.
+pub fn bench_all(
+    solutions: &[Solution],
+    inputs: &Path,
+    config: BenchConfig,
+    jobs: usize,
+) -> Vec<Result<Record>> {
+    let jobs = jobs.clamp(1, solutions.len().max(1));
+    let next = AtomicUsize::new(0);
+    let mut days: Vec<Vec<Result<Record>>> = solutions.iter().map(|_| Vec::new()).collect();
+
+    thread::scope(|s| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        match solutions.get(idx) {
+                            Some(solution) => done.push((idx, bench_day(solution, inputs, config))),
+                            None => break done,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (idx, records) in done {
+                days[idx] = records;
+            }
+        }
+    });
+
+    days.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests_bench {
+    use super::*;
+
+    #[test]
+    fn stats_test() {
+        let timings = [2, 4, 4, 4, 5, 5, 7, 9].map(Duration::from_millis);
+        let stats = Stats::new(&timings, AllocStats::default());
+
+        assert_eq!(stats.samples, 8);
+        assert_eq!(stats.mean.as_micros(), 5000);
+        assert_eq!(stats.std_dev.as_micros(), 2000);
+    }
+}
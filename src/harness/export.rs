@@ -0,0 +1,162 @@
+use serde_json::{json, Value};
+
+use super::bench::Record;
+
+use std::{fmt::Write, time::Duration};
+
+pub fn to_json(records: &[Record]) -> String {
+    let records: Vec<Value> = records
+        .iter()
+        .map(|r| {
+            json!({
+                "day": r.day,
+                "entry": r.entry.to_string(),
+                "answer": r.answer,
+                "mean_ns": r.stats.mean.as_nanos() as u64,
+                "std_dev_ns": r.stats.std_dev.as_nanos() as u64,
+                "samples": r.stats.samples,
+                "allocs": r.stats.allocs,
+                "max_memory_bytes": r.stats.max_memory,
+            })
+        })
+        .collect();
+
+    // Serializing a `Value` can't fail.
+    serde_json::to_string_pretty(&records).unwrap()
+}
+
+pub fn to_csv(records: &[Record]) -> String {
+    let mut out =
+        String::from("day,entry,answer,mean_ns,std_dev_ns,samples,allocs,max_memory_bytes\n");
+
+    for r in records {
+        // Writing to a String can't fail.
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            r.day,
+            csv_field(&r.entry.to_string()),
+            csv_field(&r.answer),
+            r.stats.mean.as_nanos(),
+            r.stats.std_dev.as_nanos(),
+            r.stats.samples,
+            r.stats.allocs,
+            r.stats.max_memory,
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn to_table(records: &[Record]) -> String {
+    let mut out = String::new();
+    let total: Duration = records.iter().map(|r| r.stats.mean).sum();
+
+    writeln!(
+        out,
+        "{:>9} | {:<30} | {:<24} | {:>7} | Max Mem.",
+        "Day", "Answer", "Time", "Allocs"
+    )
+    .unwrap();
+
+    for r in records {
+        let time = format!("{:.3?} (σ {:.3?})", r.stats.mean, r.stats.std_dev);
+        writeln!(
+            out,
+            "{:>9} | {:<30} | {:<24} | {:>7} | {}",
+            r.label(),
+            r.answer,
+            time,
+            r.stats.allocs,
+            format_bytes(r.stats.max_memory),
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "Total Time: {:.3?}", total).unwrap();
+    out
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests_export {
+    use super::*;
+    use crate::harness::bench::{Entry, Stats};
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 7,
+                entry: Entry::Part(1),
+                answer: "46065".to_owned(),
+                stats: Stats {
+                    mean: Duration::from_micros(1625),
+                    std_dev: Duration::from_nanos(464_500),
+                    samples: 10,
+                    allocs: 2,
+                    max_memory: 38_093,
+                },
+            },
+            Record {
+                day: 16,
+                entry: Entry::Other("Parse"),
+                answer: "Sue, \"373\"".to_owned(),
+                stats: Stats::default(),
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_test() {
+        let expected = "day,entry,answer,mean_ns,std_dev_ns,samples,allocs,max_memory_bytes
+7,1,46065,1625000,464500,10,2,38093
+16,Parse,\"Sue, \"\"373\"\"\",0,0,0,0,0
+";
+
+        assert_eq!(expected, to_csv(&records()));
+    }
+
+    #[test]
+    fn json_test() {
+        let actual: Value = serde_json::from_str(&to_json(&records())).unwrap();
+
+        assert_eq!(actual[0]["day"], 7);
+        assert_eq!(actual[0]["entry"], "1");
+        assert_eq!(actual[0]["mean_ns"], 1_625_000);
+        assert_eq!(actual[0]["max_memory_bytes"], 38_093);
+        assert_eq!(actual[1]["entry"], "Parse");
+        assert_eq!(actual[1]["answer"], "Sue, \"373\"");
+    }
+
+    #[test]
+    fn format_bytes_test() {
+        assert_eq!(format_bytes(43), "43 B");
+        assert_eq!(format_bytes(38_093), "37.2 KB");
+        assert_eq!(format_bytes(8 * 1024 * 1024), "8.0 MB");
+    }
+}
@@ -1,8 +1,7 @@
+use advent_of_code_2015::days;
 use aoc_lib::TracingAlloc;
 use color_eyre::Result;
 
-mod days;
-
 #[global_allocator]
 static ALLOC: TracingAlloc = TracingAlloc;
 